@@ -41,383 +41,4519 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::CreatePoll { poll_id, question, options } => exec::create_poll(deps, env,info, poll_id, question, options),
-        ExecuteMsg::Vote { poll_id, vote } => exec::vote(deps, env, info, poll_id, vote)
+        ExecuteMsg::CreatePoll {
+            poll_id,
+            question,
+            options,
+            kind,
+            start,
+            end,
+            group,
+            voters,
+            merkle_root,
+            reveal_end,
+            hide_results_until_close,
+        } => {
+            exec::create_poll(
+                deps,
+                env,
+                info,
+                poll_id,
+                question,
+                options,
+                kind,
+                start,
+                end,
+                group,
+                voters,
+                merkle_root,
+                reveal_end,
+                hide_results_until_close,
+            )
+        }
+        ExecuteMsg::Vote { poll_id, vote, proof } => {
+            exec::vote(deps, env, info, poll_id, vote, proof)
+        }
+        ExecuteMsg::WeightedVote { poll_id, options } => {
+            exec::weighted_vote(deps, env, info, poll_id, options)
+        }
+        ExecuteMsg::CommitVote { poll_id, commitment } => {
+            exec::commit_vote(deps, env, info, poll_id, commitment)
+        }
+        ExecuteMsg::RevealVote { poll_id, vote, salt } => {
+            exec::reveal_vote(deps, env, info, poll_id, vote, salt)
+        }
+        ExecuteMsg::NftVote { poll_id, vote, token_ids } => {
+            exec::nft_vote(deps, env, info, poll_id, vote, token_ids)
+        }
+        ExecuteMsg::ScoreVote { poll_id, scores } => {
+            exec::score_vote(deps, env, info, poll_id, scores)
+        }
+        ExecuteMsg::QuadraticVote { poll_id, votes } => {
+            exec::quadratic_vote(deps, env, info, poll_id, votes)
+        }
+        ExecuteMsg::ClosePoll { poll_id } => exec::close_poll(deps, env, info, poll_id),
+        ExecuteMsg::AddVoters { poll_id, voters } => exec::add_voters(deps, info, poll_id, voters),
+        ExecuteMsg::RemoveVoters { poll_id, voters } => {
+            exec::remove_voters(deps, info, poll_id, voters)
+        }
+        ExecuteMsg::Receive(wrapper) => exec::receive(deps, env, info, wrapper),
+        ExecuteMsg::Withdraw { poll_id } => exec::withdraw(deps, env, info, poll_id),
     }
 }
 mod exec {
-    use cosmwasm_std::{Response, DepsMut, Env, MessageInfo, StdResult};
-    use crate::state::{Config, CONFIG, Poll, POLLS, Ballot, BALLOTS};
-    use crate::ContractError;
+    use cosmwasm_std::{
+        coins, from_binary, to_binary, Addr, BankMsg, CosmosMsg, Decimal, Decimal256, Deps,
+        Response, DepsMut, Env, MessageInfo, StdError, StdResult, Storage, Uint128, WasmMsg,
+    };
+    use cw_utils::{may_pay, nonpayable, Expiration, PaymentError, Scheduled};
+    use crate::state::{
+        CONFIG, DEPOSITS, GOVERNANCE_OPTIONS, Poll, PollKind, PollStatus, polls, PAIRWISE, POLL_COUNT,
+        TOKEN_BALLOTS, VOTERS, CLAIMS, COMMITMENTS, Ballot, ballots,
+    };
+    use crate::cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+    use crate::cw4::{Cw4QueryMsg, MemberResponse};
+    use crate::cw721::{Cw721QueryMsg, OwnerOfResponse};
+    use crate::msg::{MerkleProof, ReceiveMsg};
+    use crate::{merkle, tally, ContractError};
+    use sha2::{Digest, Sha256};
+    use std::cmp::Ordering;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_poll(
         deps: DepsMut, 
         env: Env, 
         info: MessageInfo, 
-        poll_id: String, 
+        poll_id: Option<String>, 
         question: String, 
-        options: Vec<String>) -> Result<Response, ContractError>{
+        options: Vec<String>,
+        kind: Option<PollKind>,
+        start: Option<Scheduled>,
+        end: Option<Expiration>,
+        group: Option<String>,
+        voters: Option<Vec<String>>,
+        merkle_root: Option<String>,
+        reveal_end: Option<Expiration>,
+        hide_results_until_close: Option<bool>) -> Result<Response, ContractError>{
         if options.len() > 10 { return Err(ContractError::TooManyOptions {  })}
-        let mut opts: Vec<(String, u64)> = vec![];
+        let kind = kind.unwrap_or(PollKind::SingleChoice);
+        let options = match &kind {
+            PollKind::MultipleChoice { max_choices } => {
+                if *max_choices == 0 || *max_choices as usize > options.len() {
+                    return Err(ContractError::InvalidMaxChoices {});
+                }
+                options
+            }
+            PollKind::Governance { quorum, threshold, veto_threshold } => {
+                if !options.is_empty() {
+                    return Err(ContractError::GovernanceOptions {});
+                }
+                // a poll needs more than `threshold` yes votes to pass, so it can't be 1
+                if threshold.is_zero() || *threshold >= Decimal::one() {
+                    return Err(ContractError::InvalidThreshold {});
+                }
+                if veto_threshold.is_zero() || *veto_threshold > Decimal::one() {
+                    return Err(ContractError::InvalidThreshold {});
+                }
+                if quorum.is_zero() {
+                    return Err(ContractError::InvalidQuorum {});
+                }
+                GOVERNANCE_OPTIONS.iter().map(|option| option.to_string()).collect()
+            }
+            PollKind::Cw20 { token }
+            | PollKind::Cw20Snapshot { token }
+            | PollKind::Cw721 { contract: token } => {
+                deps.api.addr_validate(token)?;
+                options
+            }
+            _ => options,
+        };
+        // ballots refer to options by their label
+        for (i, option) in options.iter().enumerate() {
+            if options[..i].contains(option) {
+                return Err(ContractError::DuplicatePollOption {});
+            }
+        }
+        let group = group.map(|group| deps.api.addr_validate(&group)).transpose()?;
+        let token_poll = matches!(
+            kind,
+            PollKind::NativeToken { .. }
+                | PollKind::Cw20 { .. }
+                | PollKind::Cw20Snapshot { .. }
+                | PollKind::Cw721 { .. }
+                | PollKind::Staking { .. }
+        );
+        // score and quadratic ballots are checked against the poll's limits before any weight
+        let own_ballots = matches!(kind, PollKind::Score { .. } | PollKind::Quadratic { .. });
+        if (token_poll || own_ballots) && group.is_some() {
+            return Err(ContractError::InvalidGroupPoll {});
+        }
+        if let Some(root) = &merkle_root {
+            if token_poll || own_ballots {
+                return Err(ContractError::InvalidMerklePoll {});
+            }
+            merkle::decode_root(root)?;
+        }
+        let snapshot_height = match kind {
+            PollKind::Cw20Snapshot { .. } => Some(env.block.height),
+            _ => group.as_ref().map(|_| env.block.height),
+        };
+        let end = end.unwrap_or_default();
+        if end.is_expired(&env.block) {
+            return Err(ContractError::InvalidPollEnd {});
+        }
+        // a start and an end in different units can't be compared
+        let ends_before_start = match (start, end) {
+            (Some(Scheduled::AtHeight(start)), Expiration::AtHeight(end)) => start >= end,
+            (Some(Scheduled::AtTime(start)), Expiration::AtTime(end)) => start >= end,
+            _ => false,
+        };
+        if ends_before_start {
+            return Err(ContractError::InvalidPollStart {});
+        }
+        if let Some(reveal_end) = reveal_end {
+            if token_poll || own_ballots || merkle_root.is_some() {
+                return Err(ContractError::InvalidCommitRevealPoll {});
+            }
+            // also rules out polls which never end
+            if reveal_end.partial_cmp(&end) != Some(Ordering::Greater) {
+                return Err(ContractError::InvalidRevealEnd {});
+            }
+        }
+        let poll_id = match poll_id {
+            Some(poll_id) => {
+                if polls().has(deps.storage, poll_id.clone()) {
+                    return Err(ContractError::PollAlreadyExists {});
+                }
+                poll_id
+            }
+            None => next_poll_id(deps.storage)?,
+        };
+        let mut opts: Vec<(String, Decimal256)> = vec![];
 
         for option in options {
-            opts.push((option, 0));
+            opts.push((option, Decimal256::zero()));
         }
         let poll = Poll {
             creator: info.sender,
-            question: question,
-            options: opts
+            question,
+            options: opts,
+            kind,
+            total_ballots: 0,
+            status: PollStatus::Open,
+            start,
+            end,
+            outcome: None,
+            snapshot_height,
+            group,
+            allowlist: voters.is_some(),
+            merkle_root,
+            reveal_end,
+            commits: 0,
+            hide_results_until_close: hide_results_until_close.unwrap_or(false),
         };
-        POLLS.save(deps.storage, poll_id.clone(), &poll)?;
+        polls().save(deps.storage, poll_id.clone(), &poll)?;
+        for voter in voters.unwrap_or_default() {
+            let voter = deps.api.addr_validate(&voter)?;
+            VOTERS.save(deps.storage, (poll_id.clone(), voter), &())?;
+        }
 
         Ok(Response::new()
             .add_attribute("action", "create_poll")
             .add_attribute("poll_id", poll_id)) 
        }
+    // skips over any ids that were already claimed explicitly by other polls
+    fn next_poll_id(storage: &mut dyn Storage) -> StdResult<String> {
+        let mut count = POLL_COUNT.may_load(storage)?.unwrap_or_default();
+        let poll_id = loop {
+            count += 1;
+            let poll_id = count.to_string();
+            if !polls().has(storage, poll_id.clone()) {
+                break poll_id;
+            }
+        };
+        POLL_COUNT.save(storage, &count)?;
+        Ok(poll_id)
+    }
+
     pub fn vote(
         deps: DepsMut, 
         env: Env, 
         info: MessageInfo,
-        poll_id: String, vote: String) -> Result<Response, ContractError>{
-        let poll = POLLS.may_load(deps.storage, poll_id.clone())?;
-        
-
-        // either no poll or update the current one
-        match poll{
-            // if found poll, update current vote option (--), vote for another option (++)
-            Some(mut poll) => {
-                BALLOTS.update(
-                    deps.storage,
-                    (info.sender, poll_id.clone()),
-                    |ballot| -> StdResult<Ballot>{
-                        match ballot {
-                            Some(ballot) => {
-                                let position_of_old_vote = poll
-                                    .options
-                                    .iter()
-                                    .position(|p| p.0 == ballot.option)
-                                    .unwrap();
-                                    poll.options[position_of_old_vote].1 -=1;
-                                Ok( Ballot{option: vote.clone() })
-                            },
-                            None => {
-                                Ok( Ballot { option: vote.clone() })
-                            }
-                        }
-                    }
-                )?;
-                
+        poll_id: String, vote: Vec<String>,
+        proof: Option<MerkleProof>) -> Result<Response, ContractError>{
+        let poll = load_open_poll(deps.storage, &env, poll_id.clone())?;
+        // the proven weight is remembered, later ballots don't need to repeat the proof
+        if let (Some(root), Some(proof)) = (&poll.merkle_root, proof) {
+            merkle::verify(root, info.sender.as_str(), proof.weight, &proof.proof)?;
+            if proof.weight.is_zero() {
+                return Err(ContractError::NoVotingPower {});
+            }
+            CLAIMS.save(deps.storage, (info.sender.clone(), poll_id.clone()), &proof.weight)?;
+        }
+        // cw20 voters can change their vote without sending more tokens. Only native token polls
+        // take coins, any others would be stuck in the contract.
+        let sent = match &poll.kind {
+            PollKind::NativeToken { denom } => may_pay(&info, denom)?,
+            _ => {
+                nonpayable(&info)?;
+                Uint128::zero()
+            }
+        };
+        choice_vote(deps, info.sender, poll_id, poll, vote, sent)
+    }
 
-                let position = poll
-                    .options
-                    .iter()
-                    .position(|p| p.0 == vote.clone());
-                if position.is_none() {
-                    return Err(ContractError::VoteOptionNotFound {})
-                }   
-                    let position = position.unwrap();
-                    
-                    poll.options[position].1 +=1; 
-                    POLLS.save(deps.storage, poll_id.clone(), &poll)?;
-                    
-                    return Ok(Response::new()
-                        .add_attribute("action", "vote")
-                        .add_attribute("poll_id", poll_id)
-                        .add_attribute(vote.to_string(), poll.options[position].1.to_string()))   
-                
+    pub fn receive(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        wrapper: Cw20ReceiveMsg,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        match from_binary(&wrapper.msg)? {
+            ReceiveMsg::Vote { poll_id, vote } => {
+                let poll = load_open_poll(deps.storage, &env, poll_id.clone())?;
+                // the sender is the token contract, only the poll's one is accepted
+                match &poll.kind {
+                    PollKind::Cw20 { token } if *token == info.sender => {}
+                    PollKind::Cw20 { .. } => return Err(ContractError::InvalidToken {}),
+                    _ => return Err(ContractError::BallotKindMismatch {}),
+                }
+                let voter = deps.api.addr_validate(&wrapper.sender)?;
+                choice_vote(deps, voter, poll_id, poll, vote, wrapper.amount)
+            }
+        }
+    }
+
+    // `sent` tokens are locked along with the ballot of a token poll, adding to its voting power
+    fn choice_vote(
+        deps: DepsMut,
+        voter: Addr,
+        poll_id: String,
+        poll: Poll,
+        vote: Vec<String>,
+        sent: Uint128,
+    ) -> Result<Response, ContractError> {
+        if let PollKind::Score { .. } | PollKind::Quadratic { .. } | PollKind::Cw721 { .. } =
+            poll.kind
+        {
+            return Err(ContractError::BallotKindMismatch {});
+        }
+        validate_choices(&poll, &vote)?;
+
+        let mut points = poll.kind.points(poll.options.len(), &vote);
+        let power = match &poll.kind {
+            PollKind::NativeToken { .. } | PollKind::Cw20 { .. } => {
+                Some(lock_tokens(deps.storage, (voter.clone(), poll_id.clone()), sent)?)
+            }
+            PollKind::Cw20Snapshot { token } => {
+                let height = poll.snapshot_height.unwrap_or_default();
+                Some(snapshot_power(deps.as_ref(), token, &voter, height)?)
+            }
+            PollKind::Staking { validators } => {
+                Some(staked_power(deps.as_ref(), &voter, validators.as_deref())?)
+            }
+            _ => None,
+        };
+        if let Some(power) = power {
+            points = scale_points(points, power)?;
+        }
+        cast_ballot(deps, voter, poll_id, poll, Ballot { options: vote, points })
+    }
+
+    // adds the tokens to the ones locked with the previous ballot, all of them count as votes
+    fn lock_tokens(
+        storage: &mut dyn Storage,
+        key: (Addr, String),
+        amount: Uint128,
+    ) -> Result<Decimal256, ContractError> {
+        let locked = DEPOSITS
+            .may_load(storage, key.clone())?
+            .unwrap_or_default()
+            .checked_add(amount)
+            .map_err(StdError::from)?;
+        if locked.is_zero() {
+            return Err(PaymentError::NoFunds {}.into());
+        }
+        DEPOSITS.save(storage, key, &locked)?;
+        Ok(voting_power(locked))
+    }
+
+    // balance of the voter at the snapshot height, which stays the same if they vote again
+    fn snapshot_power(
+        deps: Deps,
+        token: &str,
+        voter: &Addr,
+        height: u64,
+    ) -> Result<Decimal256, ContractError> {
+        let res: BalanceResponse = deps.querier.query_wasm_smart(
+            token,
+            &Cw20QueryMsg::BalanceAt {
+                address: voter.to_string(),
+                height,
+            },
+        )?;
+        if res.balance.is_zero() {
+            return Err(ContractError::NoVotingPower {});
+        }
+        Ok(voting_power(res.balance))
+    }
+
+    // tokens bonded by the voter, only counting their delegations to `validators` if set
+    fn staked_power(
+        deps: Deps,
+        voter: &Addr,
+        validators: Option<&[String]>,
+    ) -> Result<Decimal256, ContractError> {
+        let staked = deps
+            .querier
+            .query_all_delegations(voter)?
+            .into_iter()
+            .filter(|delegation| match validators {
+                Some(validators) => validators.contains(&delegation.validator),
+                None => true,
+            })
+            .try_fold(Uint128::zero(), |total, delegation| {
+                total.checked_add(delegation.amount.amount)
+            })
+            .map_err(StdError::from)?;
+        if staked.is_zero() {
+            return Err(ContractError::NoVotingPower {});
+        }
+        Ok(voting_power(staked))
+    }
+
+    // weight of the voter in the group at the given height, members without any weight can't vote
+    fn member_weight(
+        deps: Deps,
+        group: &Addr,
+        voter: &Addr,
+        height: Option<u64>,
+    ) -> Result<Decimal256, ContractError> {
+        let res: MemberResponse = deps.querier.query_wasm_smart(
+            group,
+            &Cw4QueryMsg::Member {
+                addr: voter.to_string(),
+                at_height: height,
             },
-            None => return Err(ContractError::Unauthorized),
+        )?;
+        match res.weight {
+            None => Err(ContractError::NotMember {}),
+            Some(0) => Err(ContractError::NoVotingPower {}),
+            Some(weight) => Ok(Decimal256::from_ratio(weight, 1u128)),
+        }
+    }
+
+    // multiplies the points of a ballot by the voter's weight or voting power
+    fn scale_points(
+        points: Vec<(String, Decimal256)>,
+        power: Decimal256,
+    ) -> StdResult<Vec<(String, Decimal256)>> {
+        points
+            .into_iter()
+            .map(|(option, points)| Ok((option, points.checked_mul(power)?)))
+            .collect()
+    }
+
+    // one vote per token, any amount of base units fits
+    fn voting_power(amount: Uint128) -> Decimal256 {
+        Decimal256::from_ratio(amount, 1u128)
+    }
+
+    pub fn weighted_vote(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        poll_id: String,
+        weights: Vec<(String, Decimal)>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        let poll = load_open_poll(deps.storage, &env, poll_id.clone())?;
+        if let PollKind::SingleChoice | PollKind::Governance { .. } = poll.kind {
+        } else {
+            return Err(ContractError::BallotKindMismatch {});
+        }
+        let options: Vec<String> = weights.iter().map(|(option, _)| option.clone()).collect();
+        if options.len() > poll.options.len() {
+            return Err(ContractError::TooManyChoices {});
+        }
+        validate_options(&poll, &options)?;
+        let total = weights
+            .iter()
+            .try_fold(Decimal::zero(), |total, (_, weight)| total.checked_add(*weight))
+            .map_err(|_| ContractError::InvalidWeights {})?;
+        if weights.iter().any(|(_, weight)| weight.is_zero()) || total != Decimal::one() {
+            return Err(ContractError::InvalidWeights {});
+        }
+
+        let points = weights.into_iter().map(|(option, weight)| (option, weight.into())).collect();
+        cast_ballot(deps, info.sender, poll_id, poll, Ballot { options, points })
+    }
+
+    pub fn nft_vote(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        poll_id: String,
+        vote: Vec<String>,
+        token_ids: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        let mut poll = load_open_poll(deps.storage, &env, poll_id.clone())?;
+        let contract = match &poll.kind {
+            PollKind::Cw721 { contract } => contract.clone(),
+            _ => return Err(ContractError::BallotKindMismatch {}),
+        };
+        validate_choices(&poll, &vote)?;
+        check_eligible(deps.storage, &poll_id, &poll, &info.sender)?;
+        if token_ids.is_empty() {
+            return Err(ContractError::NoVotingPower {});
+        }
+
+        for (i, token_id) in token_ids.iter().enumerate() {
+            if token_ids[..i].contains(token_id) {
+                return Err(ContractError::DuplicateToken {});
+            }
+            let res: OwnerOfResponse = deps.querier.query_wasm_smart(
+                &contract,
+                &Cw721QueryMsg::OwnerOf {
+                    token_id: token_id.clone(),
+                    include_expired: None,
+                },
+            )?;
+            if res.owner != info.sender {
+                return Err(ContractError::NotTokenOwner {});
+            }
+        }
+
+        // each token casts the ballot, replacing its previous one even if cast by a former owner
+        let points = poll.kind.points(poll.options.len(), &vote);
+        let ballot = Ballot { options: vote, points };
+        for token_id in token_ids {
+            let key = (poll_id.clone(), token_id);
+            let previous = TOKEN_BALLOTS.may_load(deps.storage, key.clone())?;
+            count_ballot(deps.storage, &poll_id, &mut poll, previous.as_ref(), &ballot)?;
+            TOKEN_BALLOTS.save(deps.storage, key, &ballot)?;
+        }
+        polls().save(deps.storage, poll_id.clone(), &poll)?;
+
+        Ok(vote_response(poll_id, &poll, &ballot))
+    }
+
+    pub fn score_vote(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        poll_id: String,
+        scores: Vec<(String, u64)>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        let poll = load_open_poll(deps.storage, &env, poll_id.clone())?;
+        let max_score = match poll.kind {
+            PollKind::Score { max_score } => max_score,
+            _ => return Err(ContractError::BallotKindMismatch {}),
+        };
+        let options: Vec<String> = scores.iter().map(|(option, _)| option.clone()).collect();
+        validate_choices(&poll, &options)?;
+        if scores.iter().any(|(_, score)| *score > max_score) {
+            return Err(ContractError::ScoreTooHigh {});
+        }
+
+        let points = scores
+            .into_iter()
+            .filter(|(_, score)| *score > 0)
+            .map(|(option, score)| (option, Decimal256::from_ratio(score, 1u128)))
+            .collect();
+        cast_ballot(deps, info.sender, poll_id, poll, Ballot { options, points })
+    }
+
+    pub fn quadratic_vote(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        poll_id: String,
+        votes: Vec<(String, u64)>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        let poll = load_open_poll(deps.storage, &env, poll_id.clone())?;
+        let credits = match poll.kind {
+            PollKind::Quadratic { credits } => credits,
+            _ => return Err(ContractError::BallotKindMismatch {}),
+        };
+        let options: Vec<String> = votes.iter().map(|(option, _)| option.clone()).collect();
+        validate_choices(&poll, &options)?;
+        let points: Vec<(String, Decimal256)> = votes
+            .into_iter()
+            .filter(|(_, n)| *n > 0)
+            .map(|(option, n)| (option, Decimal256::from_ratio(n, 1u128)))
+            .collect();
+        // the previous ballot is replaced as a whole, so its credits are available again
+        if tally::quadratic_cost(&points) > Decimal256::from_ratio(credits, 1u128) {
+            return Err(ContractError::InsufficientCredits {});
+        }
+
+        cast_ballot(deps, info.sender, poll_id, poll, Ballot { options, points })
+    }
+
+    pub fn commit_vote(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        poll_id: String,
+        commitment: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        let mut poll = load_poll_in(deps.storage, &env, poll_id.clone(), PollStatus::Open)?;
+        if poll.reveal_end.is_none() {
+            return Err(ContractError::BallotKindMismatch {});
+        }
+        let mut hash = [0u8; 32];
+        hex::decode_to_slice(&commitment, &mut hash)
+            .map_err(|_| ContractError::InvalidCommitment {})?;
+        check_eligible(deps.storage, &poll_id, &poll, &info.sender)?;
+
+        let key = (info.sender, poll_id.clone());
+        if !COMMITMENTS.has(deps.storage, key.clone()) {
+            poll.commits += 1;
+            polls().save(deps.storage, poll_id.clone(), &poll)?;
+        }
+        COMMITMENTS.save(deps.storage, key, &hex::encode(hash))?;
+
+        Ok(Response::new()
+            .add_attribute("action", "commit_vote")
+            .add_attribute("poll_id", poll_id))
+    }
+
+    pub fn reveal_vote(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        poll_id: String,
+        vote: Vec<String>,
+        salt: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        let poll = load_poll_in(deps.storage, &env, poll_id.clone(), PollStatus::Revealing)?;
+        let key = (info.sender.clone(), poll_id.clone());
+        let commitment = COMMITMENTS
+            .may_load(deps.storage, key.clone())?
+            .ok_or(ContractError::NoCommitment {})?;
+        // encoded as JSON, so that no part of the options can be moved into the salt
+        let revealed = Sha256::digest(to_binary(&(&vote, &salt))?.as_slice());
+        if hex::encode(revealed) != commitment {
+            return Err(ContractError::InvalidReveal {});
+        }
+        // a ballot is revealed once
+        COMMITMENTS.remove(deps.storage, key);
+
+        choice_vote(deps, info.sender, poll_id, poll, vote, Uint128::zero())
+    }
+
+    fn load_open_poll(storage: &dyn Storage, env: &Env, poll_id: String) -> Result<Poll, ContractError> {
+        let poll = load_poll_in(storage, env, poll_id, PollStatus::Open)?;
+        // ballots of commit-reveal polls are only cast by revealing them
+        if poll.reveal_end.is_some() {
+            return Err(ContractError::BallotKindMismatch {});
+        }
+        Ok(poll)
+    }
+
+    // loads a poll which must currently be in the given phase
+    fn load_poll_in(
+        storage: &dyn Storage,
+        env: &Env,
+        poll_id: String,
+        phase: PollStatus,
+    ) -> Result<Poll, ContractError> {
+        let poll = match polls().may_load(storage, poll_id)? {
+            Some(poll) => poll,
+            None => return Err(ContractError::PollNotFound {}),
+        };
+        match poll.status_at(&env.block) {
+            status if status == phase => Ok(poll),
+            PollStatus::Pending => Err(ContractError::PollNotStarted {}),
+            PollStatus::Open => Err(ContractError::RevealNotStarted {}),
+            PollStatus::Revealing | PollStatus::Expired => Err(ContractError::PollExpired {}),
+            PollStatus::Closed => Err(ContractError::PollClosed {}),
+        }
+    }
+
+    // stores the voter's ballot, taking back the points of their previous one (--) before adding the new ones (++)
+    fn cast_ballot(
+        deps: DepsMut,
+        voter: Addr,
+        poll_id: String,
+        mut poll: Poll,
+        mut ballot: Ballot,
+    ) -> Result<Response, ContractError> {
+        check_eligible(deps.storage, &poll_id, &poll, &voter)?;
+        if let Some(group) = &poll.group {
+            let weight = member_weight(deps.as_ref(), group, &voter, poll.snapshot_height)?;
+            ballot.points = scale_points(ballot.points, weight)?;
+        }
+        if poll.merkle_root.is_some() {
+            let claimed = CLAIMS
+                .may_load(deps.storage, (voter.clone(), poll_id.clone()))?
+                .ok_or(ContractError::NotEligible {})?;
+            ballot.points = scale_points(ballot.points, voting_power(claimed))?;
+        }
+
+        let key = (voter, poll_id.clone());
+        let previous = ballots().may_load(deps.storage, key.clone())?;
+        count_ballot(deps.storage, &poll_id, &mut poll, previous.as_ref(), &ballot)?;
+
+        ballots().save(deps.storage, key, &ballot)?;
+        polls().save(deps.storage, poll_id.clone(), &poll)?;
+
+        Ok(vote_response(poll_id, &poll, &ballot))
+    }
+
+    fn check_eligible(
+        storage: &dyn Storage,
+        poll_id: &str,
+        poll: &Poll,
+        voter: &Addr,
+    ) -> Result<(), ContractError> {
+        if poll.allowlist && !VOTERS.has(storage, (poll_id.to_string(), voter.clone())) {
+            return Err(ContractError::NotEligible {});
+        }
+        Ok(())
+    }
+
+    // updates the poll's tallies, the poll itself is left for the caller to save
+    fn count_ballot(
+        storage: &mut dyn Storage,
+        poll_id: &str,
+        poll: &mut Poll,
+        previous: Option<&Ballot>,
+        ballot: &Ballot,
+    ) -> StdResult<()> {
+        match previous {
+            Some(previous) => {
+                for (option, points) in &previous.points {
+                    if let Some(position) = poll.options.iter().position(|p| &p.0 == option) {
+                        poll.options[position].1 = poll.options[position].1.checked_sub(*points)?;
+                    }
+                }
+            }
+            None => poll.total_ballots += 1,
+        }
+        for (option, points) in &ballot.points {
+            // ballots are validated against the poll options beforehand
+            if let Some(position) = poll.options.iter().position(|p| &p.0 == option) {
+                poll.options[position].1 = poll.options[position].1.checked_add(*points)?;
+            }
+        }
+        // the tallies must add up too, for the shares and the governance outcome
+        poll.options
+            .iter()
+            .try_fold(Decimal256::zero(), |total, (_, tally)| total.checked_add(*tally))?;
+
+        if poll.kind == PollKind::RankedChoice {
+            let options: Vec<String> = poll.options.iter().map(|p| p.0.clone()).collect();
+            let mut pairwise = PAIRWISE
+                .may_load(storage, poll_id.to_string())?
+                .unwrap_or_else(|| vec![vec![Decimal256::zero(); options.len()]; options.len()]);
+            if let Some(previous) = previous {
+                let (ranking, power) = (&previous.options, previous.power());
+                tally::record_preferences(&mut pairwise, &options, ranking, power, true);
+            }
+            let (ranking, power) = (&ballot.options, ballot.power());
+            tally::record_preferences(&mut pairwise, &options, ranking, power, false);
+            PAIRWISE.save(storage, poll_id.to_string(), &pairwise)?;
+        }
+        Ok(())
+    }
+
+    // reports the new tallies of the options the ballot gave points to, unless they are hidden
+    fn vote_response(poll_id: String, poll: &Poll, ballot: &Ballot) -> Response {
+        let mut res = Response::new()
+            .add_attribute("action", "vote")
+            .add_attribute("poll_id", poll_id);
+        // ballots are only cast before the poll ends
+        if poll.hide_results_until_close {
+            return res;
+        }
+        for (option, _) in &ballot.points {
+            if let Some((_, tally)) = poll.options.iter().find(|p| &p.0 == option) {
+                res = res.add_attribute(option, tally.to_string());
             }
-                 
         }
+        res
+    }
+
+    fn validate_choices(poll: &Poll, choices: &[String]) -> Result<(), ContractError> {
+        if choices.len() > poll.kind.max_choices(poll.options.len()) {
+            return Err(ContractError::TooManyChoices {});
+        }
+        validate_options(poll, choices)
+    }
+
+    // options must be non empty, distinct and part of the poll
+    fn validate_options(poll: &Poll, choices: &[String]) -> Result<(), ContractError> {
+        if choices.is_empty() {
+            return Err(ContractError::EmptyVote {});
+        }
+        for (i, choice) in choices.iter().enumerate() {
+            if choices[..i].contains(choice) {
+                return Err(ContractError::DuplicateVoteOption {});
+            }
+            if !poll.options.iter().any(|p| &p.0 == choice) {
+                return Err(ContractError::VoteOptionNotFound {});
+            }
+        }
+        Ok(())
+    }
+
+    pub fn close_poll(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        poll_id: String,
+    ) -> Result<Response, ContractError> {
+        let mut poll = polls()
+            .may_load(deps.storage, poll_id.clone())?
+            .ok_or(ContractError::PollNotFound {})?;
+
+        // only the poll creator or the contract admin can close a poll
+        let config = CONFIG.load(deps.storage)?;
+        if info.sender != poll.creator && info.sender != config.admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if poll.status == PollStatus::Closed {
+            return Err(ContractError::PollClosed {});
+        }
+
+        poll.status = PollStatus::Closed;
+        let mut res = Response::new()
+            .add_attribute("action", "close_poll")
+            .add_attribute("poll_id", poll_id.clone());
+        if let PollKind::Governance { quorum, threshold, veto_threshold } = poll.kind {
+            let outcome = tally::governance_outcome(&poll.options, quorum, threshold, veto_threshold);
+            res = res.add_attribute("outcome", outcome.to_string());
+            poll.outcome = Some(outcome);
+        }
+        polls().save(deps.storage, poll_id, &poll)?;
+
+        Ok(res)
+    }
+
+    pub fn add_voters(
+        deps: DepsMut,
+        info: MessageInfo,
+        poll_id: String,
+        voters: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        load_allowlist_poll(deps.storage, &info, poll_id.clone())?;
+        for voter in &voters {
+            let voter = deps.api.addr_validate(voter)?;
+            VOTERS.save(deps.storage, (poll_id.clone(), voter), &())?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "add_voters")
+            .add_attribute("poll_id", poll_id)
+            .add_attribute("voters", voters.len().to_string()))
+    }
+
+    pub fn remove_voters(
+        deps: DepsMut,
+        info: MessageInfo,
+        poll_id: String,
+        voters: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        load_allowlist_poll(deps.storage, &info, poll_id.clone())?;
+        for voter in &voters {
+            let voter = deps.api.addr_validate(voter)?;
+            VOTERS.remove(deps.storage, (poll_id.clone(), voter));
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "remove_voters")
+            .add_attribute("poll_id", poll_id)
+            .add_attribute("voters", voters.len().to_string()))
+    }
+
+    // only the poll creator manages its allowlist
+    fn load_allowlist_poll(
+        storage: &dyn Storage,
+        info: &MessageInfo,
+        poll_id: String,
+    ) -> Result<Poll, ContractError> {
+        let poll = polls()
+            .may_load(storage, poll_id)?
+            .ok_or(ContractError::PollNotFound {})?;
+        if info.sender != poll.creator {
+            return Err(ContractError::Unauthorized);
+        }
+        if !poll.allowlist {
+            return Err(ContractError::NoAllowlist {});
+        }
+        Ok(poll)
+    }
+
+    pub fn withdraw(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        poll_id: String,
+    ) -> Result<Response, ContractError> {
+        let poll = polls()
+            .may_load(deps.storage, poll_id.clone())?
+            .ok_or(ContractError::PollNotFound {})?;
+        let status = poll.status_at(&env.block);
+        if let PollStatus::Pending | PollStatus::Open | PollStatus::Revealing = status {
+            return Err(ContractError::PollNotEnded {});
+        }
+
+        let key = (info.sender.clone(), poll_id.clone());
+        let amount = DEPOSITS
+            .may_load(deps.storage, key.clone())?
+            .ok_or(ContractError::NothingToWithdraw {})?;
+        DEPOSITS.remove(deps.storage, key);
+
+        let msg: CosmosMsg = match poll.kind {
+            PollKind::NativeToken { denom } => BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(amount.u128(), denom),
+            }
+            .into(),
+            PollKind::Cw20 { token } => WasmMsg::Execute {
+                contract_addr: token,
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: info.sender.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+            _ => return Err(ContractError::NothingToWithdraw {}),
+        };
+
+        Ok(Response::new()
+            .add_message(msg)
+            .add_attribute("action", "withdraw")
+            .add_attribute("poll_id", poll_id)
+            .add_attribute("amount", amount))
+    }
 
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::AllPolls {  } => query::query_all_polls(deps, env),
+        QueryMsg::AllPolls { start_after, limit, order } => {
+            query::query_all_polls(deps, env, start_after, limit, order)
+        }
         QueryMsg::Poll { poll_id } => query::query_poll(deps, env, poll_id),
+        QueryMsg::Results { poll_id } => query::query_results(deps, env, poll_id),
+        QueryMsg::RunoffResults { poll_id } => query::query_runoff_results(deps, env, poll_id),
+        QueryMsg::SchulzeResults { poll_id } => query::query_schulze_results(deps, env, poll_id),
         QueryMsg::Vote { poll_id, address } => query::query_vote(deps, env, poll_id, address),
+        QueryMsg::CommitReveal { poll_id } => query::query_commit_reveal(deps, env, poll_id),
+        QueryMsg::TokenVote { poll_id, token_id } => {
+            query::query_token_vote(deps, env, poll_id, token_id)
+        }
+        QueryMsg::Ballots { poll_id, start_after, limit } => {
+            query::query_ballots(deps, env, poll_id, start_after, limit)
+        }
+        QueryMsg::VotesByVoter { address, start_after, limit } => {
+            query::query_votes_by_voter(deps, env, address, start_after, limit)
+        }
+        QueryMsg::EligibleVoters { poll_id, start_after, limit } => {
+            query::query_eligible_voters(deps, env, poll_id, start_after, limit)
+        }
+        QueryMsg::PollsByCreator { creator, start_after, limit } => {
+            query::query_polls_by_creator(deps, env, creator, start_after, limit)
+        }
+        QueryMsg::PollsByStatus { status, start_after, limit } => {
+            query::query_polls_by_status(deps, env, status, start_after, limit)
+        }
         QueryMsg::Config {  } => query::query_config(deps, env)
     }
 }
 
 mod query {
-    use std::sync::Condvar;
+    use cosmwasm_std::{
+        Env, Decimal256, Deps, Order, StdError, StdResult, Binary, to_binary, Uint128,
+    };
+    use cw_storage_plus::{Bound, PrimaryKey};
 
-    use cosmwasm_std::{Env, Deps, StdResult, Binary, to_binary};
+    use crate::{state::{Poll, PollKind, PollStatus, polls, ballots, CLAIMS, CONFIG, DEPOSITS, PAIRWISE, TOKEN_BALLOTS, VOTERS}, msg::{AllPollsResponse, BallotsResponse, CommitRevealResponse, EligibleVotersResponse, TokenVoteResponse, VoteResponse, VotesByVoterResponse, ConfigResponse, OptionResult, PollResponse, PollsByStatusResponse, ResultsResponse, RunoffResultsResponse, SchulzeResultsResponse, SortOrder}, tally};
 
-    use crate::{state::{POLLS, BALLOTS, CONFIG}, msg::{AllPollsResponse, VoteResponse, ConfigResponse, PollResponse}};
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
 
-    pub fn query_all_polls(deps: Deps, env : Env) -> StdResult<Binary> {
-        let polls = POLLS
-            .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-            .map(|p| Ok(p?.1))
+    pub fn query_all_polls(
+        deps: Deps,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<SortOrder>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let order: Order = order.unwrap_or(SortOrder::Ascending).into();
+        let (min, max) = range_bounds(start_after, order);
+
+        let polls = polls()
+            .range(deps.storage, min, max, order)
+            .take(limit)
+            .map(|p| {
+                let (poll_id, poll) = p?;
+                Ok((poll_id, with_current_status(poll, &env)))
+            })
             .collect::<StdResult<Vec<_>>>()?;
         to_binary(&AllPollsResponse{polls})
     }
 
+    pub fn query_votes_by_voter(
+        deps: Deps,
+        env: Env,
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let addr = deps.api.addr_validate(&address)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let min = start_after.map(Bound::exclusive);
 
-    pub fn query_poll(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
-        let poll = POLLS.may_load(deps.storage, poll_id)?;
-        to_binary(&PollResponse { poll })
+        let mut votes = vec![];
+        for ballot in ballots()
+            .prefix(addr)
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+        {
+            let (poll_id, ballot) = ballot?;
+            if !results_hidden(deps, &env, &poll_id)? {
+                votes.push((poll_id, ballot));
+            }
+        }
+        to_binary(&VotesByVoterResponse { votes })
     }
-    pub fn query_vote(deps: Deps, env: Env, poll_id : String, address: String) -> StdResult<Binary> {
-        let addr = deps.api.addr_validate(&address).unwrap();
-        let vote = BALLOTS.may_load(deps.storage, (addr, poll_id))?;
-        to_binary(&VoteResponse{vote})
 
+    pub fn query_eligible_voters(
+        deps: Deps,
+        _env: Env,
+        poll_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let min = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?
+            .map(Bound::exclusive);
+
+        let voters = VOTERS
+            .prefix(poll_id)
+            .keys(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .map(|voter| Ok(voter?.to_string()))
+            .collect::<StdResult<Vec<_>>>()?;
+        to_binary(&EligibleVotersResponse { voters })
     }
-    pub fn query_config(deps: Deps, env: Env) -> StdResult<Binary> {
-        let config = CONFIG.load(deps.storage).unwrap();
-        let admin = config.admin.to_string();
-        to_binary(&ConfigResponse{admin})
+
+    pub fn query_ballots(
+        deps: Deps,
+        env: Env,
+        poll_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        // the ballots would let anyone recount hidden tallies
+        let poll = polls().load(deps.storage, poll_id.clone())?;
+        if poll.results_hidden_at(&env.block) {
+            return to_binary(&BallotsResponse { ballots: vec![] });
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let min = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?
+            .map(|addr| Bound::exclusive((addr, poll_id.clone())));
+
+        let ballots = ballots()
+            .idx
+            .poll
+            .prefix(poll_id)
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .map(|b| {
+                let ((voter, _), ballot) = b?;
+                Ok((voter.to_string(), ballot))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        to_binary(&BallotsResponse { ballots })
     }
-}
-#[cfg(test)]
-mod tests {
-    use cosmwasm_std::{attr, from_binary};
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info}; use crate::ContractError;
-    use crate::contract::{instantiate, execute, query};
-    use crate::msg::{
-        AllPollsResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, VoteResponse, ConfigResponse,
-    };
 
-    pub const ADDR1: &str = "addr1";
-    pub const ADDR2: &str = "addr2";
+    pub fn query_polls_by_creator(
+        deps: Deps,
+        env: Env,
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let creator = deps.api.addr_validate(&creator)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let min = start_after.map(Bound::exclusive);
 
-    #[test]
-    fn test_instantiate() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let polls = polls()
+            .idx
+            .creator
+            .prefix(creator)
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .map(|p| {
+                let (poll_id, poll) = p?;
+                Ok((poll_id, with_current_status(poll, &env)))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        to_binary(&AllPollsResponse { polls })
+    }
 
-        let msg = InstantiateMsg { admin : None};
+    pub fn query_polls_by_status(
+        deps: Deps,
+        env: Env,
+        status: PollStatus,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let min = start_after.map(Bound::exclusive);
 
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        // pending, revealing and expired polls are stored as open, the exact status is only known at
+        // query time
+        let stored_status = match status {
+            PollStatus::Closed => PollStatus::Closed,
+            _ => PollStatus::Open,
+        };
+        // the limit bounds the polls scanned rather than the ones returned, so filtering a long
+        // index can't run out of gas
+        let scanned = polls()
+            .idx
+            .status
+            .prefix(stored_status.to_string())
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .map(|p| {
+                let (poll_id, poll) = p?;
+                Ok((poll_id, with_current_status(poll, &env)))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let last_scanned = match scanned.last() {
+            Some((poll_id, _)) if scanned.len() == limit => Some(poll_id.clone()),
+            _ => None,
+        };
+        let polls = scanned.into_iter().filter(|(_, poll)| poll.status == status).collect();
+        to_binary(&PollsByStatusResponse { polls, last_scanned })
+    }
 
-        assert_eq!(
-            res.attributes,
-            vec![attr("action", "instantiate"), attr("admin", ADDR1)]
-        ) 
-   }
+    // start_after is an exclusive lower bound when ascending, and an exclusive upper one otherwise
+    fn range_bounds<'a, K>(
+        start_after: Option<K>,
+        order: Order,
+    ) -> (Option<Bound<'a, K>>, Option<Bound<'a, K>>)
+    where
+        K: PrimaryKey<'a>,
+    {
+        let bound = start_after.map(Bound::exclusive);
+        match order {
+            Order::Ascending => (bound, None),
+            Order::Descending => (None, bound),
+        }
+    }
 
-   #[test]
-   fn test_instantiate_with_admin(){
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
 
-        let msg = InstantiateMsg { admin : Some(ADDR2.to_string())};
+    pub fn query_poll(deps: Deps, env: Env, poll_id: String) -> StdResult<Binary> {
+        let poll = polls()
+            .may_load(deps.storage, poll_id)?
+            .map(|poll| with_current_status(poll, &env));
+        to_binary(&PollResponse { poll })
+    }
 
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+    pub fn query_results(deps: Deps, env: Env, poll_id: String) -> StdResult<Binary> {
+        let poll = polls().load(deps.storage, poll_id)?;
+        let hidden = poll.results_hidden_at(&env.block);
+        let poll = with_current_status(poll, &env);
 
-        assert_eq!(
-            res.attributes,
-            vec![attr("action", "instantiate"), attr("admin", ADDR2)]
-        ) 
-   }
+        let total_ballots = poll.total_ballots;
+        let total = poll
+            .options
+            .iter()
+            .try_fold(Decimal256::zero(), |total, (_, count)| total.checked_add(*count))?;
+        let ratio = |count: Decimal256, total: Decimal256| {
+            if total.is_zero() {
+                Decimal256::zero()
+            } else {
+                count / total
+            }
+        };
+        let options = poll
+            .options
+            .iter()
+            .map(|(option, count)| OptionResult {
+                option: option.clone(),
+                count: *count,
+                share: ratio(*count, total),
+                average: ratio(*count, Decimal256::from_ratio(total_ballots, 1u128)),
+            })
+            .collect::<Vec<_>>();
+
+        let mut counts: Vec<Decimal256> = options.iter().map(|o| o.count).collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        counts.dedup();
+        if hidden {
+            counts.clear();
+        }
+        let ranking = counts
+            .into_iter()
+            .map(|count| {
+                options
+                    .iter()
+                    .filter(|o| o.count == count)
+                    .map(|o| o.option.clone())
+                    .collect()
+            })
+            .collect();
+
+        let winners = match options.iter().map(|o| o.count).max() {
+            Some(max) if !max.is_zero() => options
+                .iter()
+                .filter(|o| o.count == max)
+                .map(|o| o.option.clone())
+                .collect(),
+            _ => vec![],
+        };
+
+        to_binary(&ResultsResponse {
+            status: poll.status,
+            total_ballots,
+            tie: winners.len() > 1,
+            options,
+            ranking,
+            winners,
+            outcome: match poll.kind {
+                _ if hidden => None,
+                PollKind::Governance { quorum, threshold, veto_threshold } => poll.outcome.or_else(|| {
+                    Some(tally::governance_outcome(&poll.options, quorum, threshold, veto_threshold))
+                }),
+                _ => None,
+            },
+        })
+    }
+
+    pub fn query_runoff_results(deps: Deps, env: Env, poll_id: String) -> StdResult<Binary> {
+        let poll = polls().load(deps.storage, poll_id.clone())?;
+        if poll.kind != PollKind::RankedChoice {
+            return Err(StdError::generic_err("Poll is not a ranked choice poll"));
+        }
+        if poll.results_hidden_at(&env.block) {
+            return to_binary(&RunoffResultsResponse {
+                status: poll.status_at(&env.block),
+                rounds: vec![],
+                winners: vec![],
+                tie: false,
+            });
+        }
+
+        let rankings = ballots()
+            .idx
+            .poll
+            .prefix(poll_id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|b| {
+                let (_, ballot) = b?;
+                let power = ballot.power();
+                Ok((ballot.options, power))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let options: Vec<String> = poll.options.iter().map(|(option, _)| option.clone()).collect();
+        let (rounds, winners) = tally::instant_runoff(&options, &rankings);
+
+        to_binary(&RunoffResultsResponse {
+            status: poll.status_at(&env.block),
+            rounds,
+            tie: winners.len() > 1,
+            winners,
+        })
+    }
+
+    pub fn query_schulze_results(deps: Deps, env: Env, poll_id: String) -> StdResult<Binary> {
+        let poll = polls().load(deps.storage, poll_id.clone())?;
+        if poll.kind != PollKind::RankedChoice {
+            return Err(StdError::generic_err("Poll is not a ranked choice poll"));
+        }
+        if poll.results_hidden_at(&env.block) {
+            return to_binary(&SchulzeResultsResponse {
+                status: poll.status_at(&env.block),
+                ranking: vec![],
+                winners: vec![],
+                tie: false,
+                pairwise: vec![],
+            });
+        }
+
+        let options: Vec<String> = poll.options.iter().map(|(option, _)| option.clone()).collect();
+        let pairwise = PAIRWISE
+            .may_load(deps.storage, poll_id)?
+            .unwrap_or_else(|| vec![vec![Decimal256::zero(); options.len()]; options.len()]);
+        let ranking: Vec<Vec<String>> = tally::schulze(&pairwise)
+            .into_iter()
+            .map(|group| group.into_iter().map(|i| options[i].clone()).collect())
+            .collect();
+        let winners = match ranking.first() {
+            Some(first) if poll.total_ballots > 0 => first.clone(),
+            _ => vec![],
+        };
+
+        to_binary(&SchulzeResultsResponse {
+            status: poll.status_at(&env.block),
+            ranking,
+            tie: winners.len() > 1,
+            winners,
+            pairwise,
+        })
+    }
+
+    // Reports pending and expired polls as such, without anyone having to close them, and
+    // redacts the tallies of polls hiding them until they end
+    fn with_current_status(mut poll: Poll, env: &Env) -> Poll {
+        if poll.results_hidden_at(&env.block) {
+            for (_, count) in poll.options.iter_mut() {
+                *count = Decimal256::zero();
+            }
+        }
+        poll.status = poll.status_at(&env.block);
+        poll
+    }
+    pub fn query_vote(
+        deps: Deps,
+        env: Env,
+        poll_id: String,
+        address: String,
+    ) -> StdResult<Binary> {
+        let addr = deps.api.addr_validate(&address)?;
+        let vote = ballots().may_load(deps.storage, (addr.clone(), poll_id.clone()))?;
+        let remaining_credits = match polls().may_load(deps.storage, poll_id.clone())? {
+            Some(Poll { kind: PollKind::Quadratic { credits }, .. }) => {
+                let spent = vote
+                    .as_ref()
+                    .map_or(Decimal256::zero(), |ballot| tally::quadratic_cost(&ballot.points));
+                let remaining = Decimal256::from_ratio(credits, 1u128).saturating_sub(spent);
+                // never more than the credits
+                Some(Uint128::try_from(remaining.to_uint_floor())?.u128() as u64)
+            }
+            _ => None,
+        };
+        let locked = DEPOSITS.may_load(deps.storage, (addr.clone(), poll_id.clone()))?;
+        let weight = CLAIMS.may_load(deps.storage, (addr, poll_id.clone()))?;
+        let vote = if results_hidden(deps, &env, &poll_id)? { None } else { vote };
+        to_binary(&VoteResponse{vote, remaining_credits, locked, weight})
+
+    }
+    pub fn query_commit_reveal(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+        let poll = polls().load(deps.storage, poll_id)?;
+        to_binary(&CommitRevealResponse {
+            commits: poll.commits,
+            reveals: poll.total_ballots,
+        })
+    }
+
+    pub fn query_token_vote(
+        deps: Deps,
+        env: Env,
+        poll_id: String,
+        token_id: String,
+    ) -> StdResult<Binary> {
+        let vote = if results_hidden(deps, &env, &poll_id)? {
+            None
+        } else {
+            TOKEN_BALLOTS.may_load(deps.storage, (poll_id, token_id))?
+        };
+        to_binary(&TokenVoteResponse { vote })
+    }
+
+    // whether the poll currently hides its tallies, along with the ballots they are made of
+    fn results_hidden(deps: Deps, env: &Env, poll_id: &str) -> StdResult<bool> {
+        Ok(match polls().may_load(deps.storage, poll_id.to_string())? {
+            Some(poll) => poll.results_hidden_at(&env.block),
+            None => false,
+        })
+    }
+
+    pub fn query_config(deps: Deps, _env: Env) -> StdResult<Binary> {
+        let config = CONFIG.load(deps.storage).unwrap();
+        let admin = config.admin.to_string();
+        to_binary(&ConfigResponse{admin})
+    }
+}
+#[cfg(test)]
+mod tests {
+    use anyhow::{bail, Result as AnyResult};
+    use cosmwasm_std::{
+        attr, coin, coins, from_binary, to_binary, Addr, AllDelegationsResponse, Api, BankMsg,
+        Binary, BlockInfo, ContractResult, CustomQuery, Decimal, Decimal256, Delegation, Deps,
+        FullDelegation, Order, Querier, StakingMsg, StakingQuery, StdResult, Storage, SubMsg,
+        SystemError, SystemResult, Uint128, WasmMsg, WasmQuery,
+    };
+    use cw_multi_test::{
+        AppBuilder, AppResponse, ContractWrapper, CosmosRouter, Executor, Module, Staking,
+        StakingSudo,
+    };
+    use cw_storage_plus::Map;
+    use schemars::JsonSchema;
+    use serde::de::DeserializeOwned;
+    use crate::cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+    use crate::cw4::{Cw4QueryMsg, MemberResponse};
+    use crate::cw721::{Cw721QueryMsg, OwnerOfResponse};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info}; use crate::ContractError;
+    use crate::contract::{instantiate, execute, query};
+    use crate::state::{Ballot, GovernanceOutcome, PollKind, PollStatus};
+    use cw_utils::{Expiration, PaymentError, Scheduled};
+    use crate::msg::{
+        AllPollsResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, VoteResponse, ConfigResponse,
+        PollsByStatusResponse, SortOrder, BallotsResponse, VotesByVoterResponse, ResultsResponse,
+        OptionResult,
+        RunoffResultsResponse, RunoffRound, SchulzeResultsResponse, ReceiveMsg, TokenVoteResponse,
+        EligibleVotersResponse, MerkleProof, CommitRevealResponse,
+    };
+    use sha2::{Digest, Sha256};
+
+    pub const ADDR1: &str = "addr1";
+    pub const ADDR2: &str = "addr2";
+
+    #[test]
+    fn test_instantiate() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = InstantiateMsg { admin : None};
+
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "instantiate"), attr("admin", ADDR1)]
+        ) 
+   }
+
+   #[test]
+   fn test_instantiate_with_admin(){
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = InstantiateMsg { admin : Some(ADDR2.to_string())};
+
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "instantiate"), attr("admin", ADDR2)]
+        ) 
+   }
+
+   #[test]
+   fn test_execute_create_poll_valid(){
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info(ADDR1, &[]);
+    // Instantiate the contract
+    let msg = InstantiateMsg { admin: None };
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::CreatePoll {
+        poll_id: Some("some_id".to_string()),
+        question: "What's your favourite Cosmos coin?".to_string(),
+        options: vec![
+            "Cosmos Hub".to_string(),
+            "Juno".to_string(),
+            "Osmosis".to_string(),
+        ],
+        kind: None,
+        start: None,
+        end: None,
+        group: None,
+        voters: None,
+        merkle_root: None,
+        reveal_end: None,
+        hide_results_until_close: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "create_poll"), attr("poll_id", "some_id")]
+    )
+   }
+
+   #[test]
+    fn test_execute_create_poll_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite number?".to_string(),
+            options: vec![
+                "1".to_string(),
+                "2".to_string(),
+                "3".to_string(),
+                "4".to_string(),
+                "5".to_string(),
+                "6".to_string(),
+                "7".to_string(),
+                "8".to_string(),
+                "9".to_string(),
+                "10".to_string(),
+                "11".to_string(),
+            ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+    
+        // Unwrap error to assert failure
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::TooManyOptions {  });
+
+        // Options must be distinct
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Juno".to_string(), "Osmosis".to_string(), "Juno".to_string()],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(err, ContractError::DuplicatePollOption {});
+    }
+
+    #[test]
+    fn test_execute_create_poll_existing_id() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+        // Creating a poll with the same id again must not overwrite the first one
+        let err = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::PollAlreadyExists {});
+    }
+
+    #[test]
+    fn test_execute_create_poll_generated_id() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Claim the id the counter would hand out second
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("2".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: None,
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "create_poll"), attr("poll_id", "1")]
+        );
+
+        // "2" is taken, so the counter skips over it
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "create_poll"), attr("poll_id", "3")]
+        );
+    }
+    #[test]
+    fn test_execute_vote_valid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the vote, first time voting
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Change the vote
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Osmosis".to_string()],
+            proof: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"),attr("Osmosis", "1")]
+        )
+       
+    }
+    
+    #[test]
+    fn test_execute_vote_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the vote, some_id poll is not created yet.
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+        // Unwrap to assert error
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::PollNotFound {});
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Vote on a now existing poll but the option "DVPN" does not exist
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["DVPN".to_string()],
+            proof: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(err, ContractError::VoteOptionNotFound{})
+    }
+
+    #[test]
+    fn test_execute_vote_with_funds() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll which isn't a native token poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Coins sent along with a ballot are refused rather than kept
+        for msg in [
+            ExecuteMsg::Vote {
+                poll_id: "some_id".to_string(),
+                vote: vec!["Juno".to_string()],
+                proof: None,
+            },
+            ExecuteMsg::WeightedVote {
+                poll_id: "some_id".to_string(),
+                options: vec![("Juno".to_string(), Decimal::one())],
+            },
+        ] {
+            let info = mock_info(ADDR1, &coins(1000, "ujuno"));
+            let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+            assert_eq!(err, ContractError::Payment(PaymentError::NonPayable {}));
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_execute_vote_multiple_choice() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll allowing up to two choices
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Which Cosmos coins do you hold?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: Some(PollKind::MultipleChoice { max_choices: 2 }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string(), "Osmosis".to_string()],
+            proof: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "vote"),
+                attr("poll_id", "some_id"),
+                attr("Juno", "1"),
+                attr("Osmosis", "1"),
+            ]
+        );
+
+        // Changing the ballot takes back both previous choices
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            proof: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), Decimal256::one()),
+                ("Juno".to_string(), Decimal256::one()),
+                ("Osmosis".to_string(), Decimal256::zero()),
+            ]
+        );
+        assert_eq!(poll.total_ballots, 1);
+    }
+
+    #[test]
+    fn test_execute_vote_multiple_choice_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // max_choices can't exceed the number of options
+        let mut msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Which Cosmos coins do you hold?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: Some(PollKind::MultipleChoice { max_choices: 4 }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxChoices {});
+
+        if let ExecuteMsg::CreatePoll { kind, .. } = &mut msg {
+            *kind = Some(PollKind::MultipleChoice { max_choices: 2 });
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for (vote, expected) in [
+            (vec![], ContractError::EmptyVote {}),
+            (vec!["Cosmos Hub", "Juno", "Osmosis"], ContractError::TooManyChoices {}),
+            (vec!["Juno", "Juno"], ContractError::DuplicateVoteOption {}),
+            (vec!["Juno", "DVPN"], ContractError::VoteOptionNotFound {}),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id".to_string(),
+                vote: vote.into_iter().map(String::from).collect(),
+                proof: None,
+            };
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, expected);
+        }
+    }
+
+    #[test]
+    fn test_execute_weighted_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for (weights, expected) in [
+            (vec![("Juno", 70), ("Osmosis", 20)], ContractError::InvalidWeights {}),
+            (vec![("Juno", 100), ("Osmosis", 0)], ContractError::InvalidWeights {}),
+            (vec![("Juno", 50), ("Juno", 50)], ContractError::DuplicateVoteOption {}),
+            (vec![("Juno", 50), ("DVPN", 50)], ContractError::VoteOptionNotFound {}),
+        ] {
+            let msg = ExecuteMsg::WeightedVote {
+                poll_id: "some_id".to_string(),
+                options: weights
+                    .into_iter()
+                    .map(|(o, w)| (o.to_string(), Decimal::percent(w)))
+                    .collect(),
+            };
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, expected);
+        }
+
+        // Split the vote 70/30 between Juno and Osmosis
+        let msg = ExecuteMsg::WeightedVote {
+            poll_id: "some_id".to_string(),
+            options: vec![
+                ("Juno".to_string(), Decimal::percent(70)),
+                ("Osmosis".to_string(), Decimal::percent(30)),
+            ],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "vote"),
+                attr("poll_id", "some_id"),
+                attr("Juno", "0.7"),
+                attr("Osmosis", "0.3"),
+            ]
+        );
+
+        // Replacing it with a plain vote takes back the whole split
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Cosmos Hub".to_string()],
+            proof: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), Decimal256::one()),
+                ("Juno".to_string(), Decimal256::zero()),
+                ("Osmosis".to_string(), Decimal256::zero()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_borda_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a Borda poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Rank your favourite Cosmos coins".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: Some(PollKind::Borda),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // The first choice gets 2 points, the second one 1
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string(), "Osmosis".to_string(), "Cosmos Hub".to_string()],
+            proof: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "vote"),
+                attr("poll_id", "some_id"),
+                attr("Juno", "2"),
+                attr("Osmosis", "1"),
+            ]
+        );
+
+        // Score ballots are rejected
+        let msg = ExecuteMsg::ScoreVote {
+            poll_id: "some_id".to_string(),
+            scores: vec![("Juno".to_string(), 2)],
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::BallotKindMismatch {});
+
+        // ADDR1 changes their ranking, ADDR2 only ranks Osmosis
+        for (voter, ranking) in [(ADDR1, vec!["Osmosis", "Cosmos Hub"]), (ADDR2, vec!["Osmosis"])] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id".to_string(),
+                vote: ranking.into_iter().map(String::from).collect(),
+                proof: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        let counts: Vec<_> = res.options.iter().map(|o| o.count).collect();
+        assert_eq!(counts, vec![Decimal256::one(), Decimal256::zero(), Decimal256::percent(400)]);
+        assert_eq!(
+            res.ranking,
+            vec![
+                vec!["Osmosis".to_string()],
+                vec!["Cosmos Hub".to_string()],
+                vec!["Juno".to_string()],
+            ]
+        );
+        assert_eq!(res.winners, vec!["Osmosis"]);
+    }
+
+    #[test]
+    fn test_execute_score_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll rating options from 0 to 5 stars
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Rate these Cosmos coins".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: Some(PollKind::Score { max_score: 5 }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for (scores, expected) in [
+            (vec![("Juno", 6)], ContractError::ScoreTooHigh {}),
+            (vec![("Juno", 1), ("Juno", 2)], ContractError::DuplicateVoteOption {}),
+            (vec![("DVPN", 1)], ContractError::VoteOptionNotFound {}),
+        ] {
+            let msg = ExecuteMsg::ScoreVote {
+                poll_id: "some_id".to_string(),
+                scores: scores.into_iter().map(|(o, s)| (o.to_string(), s)).collect(),
+            };
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, expected);
+        }
+
+        // Picking options is not enough on a score poll
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::BallotKindMismatch {});
+
+        // ADDR1 rates twice, only their second ballot counts
+        for (voter, scores) in [
+            (ADDR1, vec![("Cosmos Hub", 5), ("Juno", 5)]),
+            (ADDR1, vec![("Cosmos Hub", 2), ("Juno", 4), ("Osmosis", 3)]),
+            (ADDR2, vec![("Cosmos Hub", 3), ("Juno", 5)]),
+        ] {
+            let msg = ExecuteMsg::ScoreVote {
+                poll_id: "some_id".to_string(),
+                scores: scores.into_iter().map(|(o, s)| (o.to_string(), s)).collect(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.total_ballots, 2);
+        let averages: Vec<_> = res.options.iter().map(|o| (o.count, o.average)).collect();
+        assert_eq!(
+            averages,
+            vec![
+                (Decimal256::percent(500), Decimal256::from_ratio(5u128, 2u128)),
+                (Decimal256::percent(900), Decimal256::from_ratio(9u128, 2u128)),
+                (Decimal256::percent(300), Decimal256::from_ratio(3u128, 2u128)),
+            ]
+        );
+        assert_eq!(res.winners, vec!["Juno"]);
+    }
+
+    #[test]
+    fn test_execute_quadratic_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Every voter gets 10 credits
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Which Cosmos coins should we list?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: Some(PollKind::Quadratic { credits: 10 }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let remaining_credits = |deps: Deps| {
+            let msg = QueryMsg::Vote {
+                poll_id: "some_id".to_string(),
+                address: ADDR1.to_string(),
+            };
+            let bin = query(deps, mock_env(), msg).unwrap();
+            from_binary::<VoteResponse>(&bin).unwrap().remaining_credits
+        };
+        assert_eq!(remaining_credits(deps.as_ref()), Some(10));
+
+        // 3 votes on Juno and 1 on Osmosis cost 9 + 1 credits
+        let msg = ExecuteMsg::QuadraticVote {
+            poll_id: "some_id".to_string(),
+            votes: vec![("Juno".to_string(), 3), ("Osmosis".to_string(), 1)],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(remaining_credits(deps.as_ref()), Some(0));
+
+        // 3 votes on Juno and 2 on Osmosis would cost 13 credits
+        let msg = ExecuteMsg::QuadraticVote {
+            poll_id: "some_id".to_string(),
+            votes: vec![("Juno".to_string(), 3), ("Osmosis".to_string(), 2)],
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientCredits {});
+
+        // Reallocating frees the credits of the previous ballot
+        let msg = ExecuteMsg::QuadraticVote {
+            poll_id: "some_id".to_string(),
+            votes: vec![("Cosmos Hub".to_string(), 2), ("Osmosis".to_string(), 2)],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(remaining_credits(deps.as_ref()), Some(2));
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), Decimal256::percent(200)),
+                ("Juno".to_string(), Decimal256::zero()),
+                ("Osmosis".to_string(), Decimal256::percent(200)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_governance_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let kind = PollKind::Governance {
+            quorum: Uint128::new(2),
+            threshold: Decimal::percent(50),
+            veto_threshold: Decimal::percent(33),
+        };
+
+        // Governance polls bring their own options
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Should we upgrade the chain?".to_string(),
+            options: vec!["yes".to_string()],
+            kind: Some(kind.clone()),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::GovernanceOptions {});
+
+        // The threshold must be within (0, 1), the veto threshold within (0, 1] and the quorum
+        // at least one vote
+        for (quorum, threshold, veto_threshold, error) in [
+            (1, 0, 33, ContractError::InvalidThreshold {}),
+            (1, 100, 33, ContractError::InvalidThreshold {}),
+            (1, 50, 0, ContractError::InvalidThreshold {}),
+            (1, 50, 101, ContractError::InvalidThreshold {}),
+            (0, 50, 33, ContractError::InvalidQuorum {}),
+        ] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some("some_id".to_string()),
+                question: "Should we upgrade the chain?".to_string(),
+                options: vec![],
+                kind: Some(PollKind::Governance {
+                    quorum: Uint128::new(quorum),
+                    threshold: Decimal::percent(threshold),
+                    veto_threshold: Decimal::percent(veto_threshold),
+                }),
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, error);
+        }
+
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Should we upgrade the chain?".to_string(),
+            options: vec![],
+            kind: Some(kind),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        let options: Vec<_> =
+            res.poll.unwrap().options.into_iter().map(|(option, _)| option).collect();
+        assert_eq!(options, vec!["yes", "no", "abstain", "no_with_veto"]);
+
+        // Only one option can be picked
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["yes".to_string(), "abstain".to_string()],
+            proof: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::TooManyChoices {});
+
+        // A single ballot does not reach the quorum
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["yes".to_string()],
+            proof: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.outcome, Some(GovernanceOutcome::QuorumNotReached));
+
+        // ADDR2 splits their vote, abstentions do not count against the threshold
+        let msg = ExecuteMsg::WeightedVote {
+            poll_id: "some_id".to_string(),
+            options: vec![
+                ("no".to_string(), Decimal::percent(25)),
+                ("abstain".to_string(), Decimal::percent(75)),
+            ],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // Close the poll, the outcome is stored on it
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "close_poll"),
+                attr("poll_id", "some_id"),
+                attr("outcome", "passed")
+            ]
+        );
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.poll.unwrap().outcome, Some(GovernanceOutcome::Passed));
+
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.outcome, Some(GovernanceOutcome::Passed));
+    }
+
+    #[test]
+    fn test_execute_native_token_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: Some(PollKind::NativeToken {
+                denom: "ujuno".to_string(),
+            }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Voting requires locking coins of the poll denom
+        let vote = |option: &str| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec![option.to_string()],
+            proof: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), vote("Juno")).unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NoFunds {}));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &coins(100, "uatom")),
+            vote("Juno"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Payment(PaymentError::ExtraDenom("uatom".to_string()))
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &coins(100, "ujuno")),
+            vote("Juno"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Juno", "100")]
+        );
+
+        // Changing the vote moves the locked coins, along with any coins sent with it
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &coins(50, "ujuno")),
+            vote("Cosmos Hub"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Cosmos Hub", "150")]
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), vote("Juno")).unwrap();
+
+        let msg = QueryMsg::Vote {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.locked, Some(Uint128::new(150)));
+
+        // Coins stay locked while the poll is open
+        let msg = ExecuteMsg::Withdraw {
+            poll_id: "some_id".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::PollNotEnded {});
+
+        // Close the poll, the tallies are final
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Withdraw {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: ADDR1.to_string(),
+                amount: coins(150, "ujuno"),
+            })]
+        );
+
+        // Nothing is left to withdraw, neither for ADDR1 nor for ADDR2 who never voted
+        for addr in [ADDR1, ADDR2] {
+            let msg = ExecuteMsg::Withdraw {
+                poll_id: "some_id".to_string(),
+            };
+            let err = execute(deps.as_mut(), env.clone(), mock_info(addr, &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::NothingToWithdraw {});
+        }
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), Decimal256::zero()),
+                ("Juno".to_string(), Decimal256::from_ratio(150u128, 1u128)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_native_token_vote_large_amounts() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll on an 18 decimals denom
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: Some(PollKind::NativeToken {
+                denom: "aevmos".to_string(),
+            }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // A million tokens and the largest possible deposit both count in full
+        let million = 1_000_000 * 10u128.pow(18);
+        for (voter, amount) in [(ADDR1, million), (ADDR2, u128::MAX)] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id".to_string(),
+                vote: vec!["Juno".to_string()],
+                proof: None,
+            };
+            let info = mock_info(voter, &coins(amount, "aevmos"));
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.options[1].count,
+            Decimal256::from_ratio(million, 1u128) + Decimal256::from_ratio(u128::MAX, 1u128)
+        );
+        assert_eq!(res.options[1].share, Decimal256::one());
+        assert_eq!(res.winners, vec!["Juno"]);
+    }
+
+    #[test]
+    fn test_execute_cw20_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: Some(PollKind::Cw20 {
+                token: "token".to_string(),
+            }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR2 sends 100 tokens with their vote
+        let receive = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ADDR2.to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Vote {
+                poll_id: "some_id".to_string(),
+                vote: vec!["Juno".to_string()],
+            })
+            .unwrap(),
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other_token", &[]),
+            receive.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidToken {});
+        let res = execute(deps.as_mut(), env.clone(), mock_info("token", &[]), receive).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Juno", "100")]
+        );
+
+        // Changing the vote moves the locked tokens, voting without any fails
+        let vote = |option: &str| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec![option.to_string()],
+            proof: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), vote("Cosmos Hub"))
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Cosmos Hub", "100")]
+        );
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), vote("Juno")).unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NoFunds {}));
+
+        // Close the poll and take the tokens back
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Withdraw {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: ADDR2.to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+    }
+
+    #[test]
+    fn test_execute_cw20_snapshot_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // ADDR1 holds 100 tokens at the poll creation height, ADDR2 none
+        let height = env.block.height;
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "token" => {
+                let balance = match from_binary(msg).unwrap() {
+                    Cw20QueryMsg::BalanceAt { address, height: at } if address == ADDR1 => {
+                        assert_eq!(at, height);
+                        Uint128::new(100)
+                    }
+                    _ => Uint128::zero(),
+                };
+                let res = to_binary(&BalanceResponse { balance }).unwrap();
+                SystemResult::Ok(ContractResult::Ok(res))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "unknown contract".to_string(),
+            }),
+        });
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: Some(PollKind::Cw20Snapshot {
+                token: "token".to_string(),
+            }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Vote a few blocks later, the balance is still read at the creation height
+        let mut later = env.clone();
+        later.block.height += 10;
+        let vote = |option: &str| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec![option.to_string()],
+            proof: None,
+        };
+        let res = execute(deps.as_mut(), later.clone(), info.clone(), vote("Juno")).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Juno", "100")]
+        );
+        let err = execute(deps.as_mut(), later.clone(), mock_info(ADDR2, &[]), vote("Juno"))
+            .unwrap_err();
+        assert_eq!(err, ContractError::NoVotingPower {});
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), later, msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(poll.snapshot_height, Some(height));
+        assert_eq!(poll.total_ballots, 1);
+    }
+
+    #[test]
+    fn test_execute_group_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // ADDR1 is a member of the group with a weight of 3, ADDR2 is not a member
+        let height = env.block.height;
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "group" => {
+                let weight = match from_binary(msg).unwrap() {
+                    Cw4QueryMsg::Member { addr, at_height } if addr == ADDR1 => {
+                        assert_eq!(at_height, Some(height));
+                        Some(3)
+                    }
+                    _ => None,
+                };
+                let res = to_binary(&MemberResponse { weight }).unwrap();
+                SystemResult::Ok(ContractResult::Ok(res))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "unknown contract".to_string(),
+            }),
+        });
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Token polls can't be restricted to a group
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: Some(PollKind::NativeToken {
+                denom: "ujuno".to_string(),
+            }),
+            start: None,
+            end: None,
+            group: Some("group".to_string()),
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidGroupPoll {});
+
+        // Neither can quadratic and score polls, their budgets apply to unweighted ballots
+        for kind in [PollKind::Quadratic { credits: 100 }, PollKind::Score { max_score: 5 }] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some("some_id".to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: Some(kind),
+                start: None,
+                end: None,
+                group: Some("group".to_string()),
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidGroupPoll {});
+        }
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: None,
+            end: None,
+            group: Some("group".to_string()),
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Members vote with their weight
+        let vote = |option: &str| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec![option.to_string()],
+            proof: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), vote("Juno")).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Juno", "3")]
+        );
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), vote("Juno"))
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotMember {});
+
+        // Changing the vote moves the whole weight
+        let msg = ExecuteMsg::WeightedVote {
+            poll_id: "some_id".to_string(),
+            options: vec![
+                ("Cosmos Hub".to_string(), Decimal::percent(50)),
+                ("Juno".to_string(), Decimal::percent(50)),
+            ],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), Decimal256::percent(150)),
+                ("Juno".to_string(), Decimal256::percent(150)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_group_ranked_choice_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // ADDR1 has a weight of 3 in the group, the other members a weight of 1
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "group" => {
+                let weight = match from_binary(msg).unwrap() {
+                    Cw4QueryMsg::Member { addr, .. } if addr == ADDR1 => Some(3),
+                    _ => Some(1),
+                };
+                let res = to_binary(&MemberResponse { weight }).unwrap();
+                SystemResult::Ok(ContractResult::Ok(res))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "unknown contract".to_string(),
+            }),
+        });
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string(), "Osmosis".to_string()],
+            kind: Some(PollKind::RankedChoice),
+            start: None,
+            end: None,
+            group: Some("group".to_string()),
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let vote = |ranking: &[&str]| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: ranking.iter().map(|option| option.to_string()).collect(),
+            proof: None,
+        };
+        for (voter, ranking) in [
+            (ADDR1, vec!["Juno", "Osmosis"]),
+            (ADDR2, vec!["Cosmos Hub", "Osmosis"]),
+            ("addr3", vec!["Osmosis", "Cosmos Hub"]),
+        ] {
+            let info = mock_info(voter, &[]);
+            let _res = execute(deps.as_mut(), env.clone(), info, vote(&ranking)).unwrap();
+        }
+
+        // ADDR1's ballot counts three times, in the runoff as well as head to head
+        let runoff_msg = QueryMsg::RunoffResults {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), runoff_msg.clone()).unwrap();
+        let res: RunoffResultsResponse = from_binary(&bin).unwrap();
+        let votes = |n: u128| Decimal256::from_ratio(n, 1u128);
+        assert_eq!(
+            res.rounds[0].tallies,
+            vec![
+                ("Cosmos Hub".to_string(), votes(1)),
+                ("Juno".to_string(), votes(3)),
+                ("Osmosis".to_string(), votes(1)),
+            ]
+        );
+        assert_eq!(res.winners, vec!["Juno"]);
+
+        let schulze_msg = QueryMsg::SchulzeResults {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), schulze_msg.clone()).unwrap();
+        let res: SchulzeResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.pairwise[1][0], votes(3));
+        assert_eq!(res.pairwise[0][1], votes(2));
+        assert_eq!(res.winners, vec!["Juno"]);
+
+        // Changing the ballot takes back its whole weight
+        let msg = vote(&["Cosmos Hub", "Juno"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), runoff_msg).unwrap();
+        let res: RunoffResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.winners, vec!["Cosmos Hub"]);
+
+        let bin = query(deps.as_ref(), env, schulze_msg).unwrap();
+        let res: SchulzeResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.pairwise[0][1], votes(5));
+        assert_eq!(res.pairwise[1][0], votes(0));
+        assert_eq!(res.winners, vec!["Cosmos Hub"]);
+    }
+
+    #[test]
+    fn test_execute_nft_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // ADDR1 owns the tokens 1 and 2, ADDR2 owns the token 3
+        let owners = |owners: [&'static str; 3]| {
+            move |query: &WasmQuery| match query {
+                WasmQuery::Smart { contract_addr, msg } if contract_addr == "nft" => {
+                    let Cw721QueryMsg::OwnerOf { token_id, .. } = from_binary(msg).unwrap();
+                    let owner = owners[token_id.parse::<usize>().unwrap() - 1].to_string();
+                    let res = to_binary(&OwnerOfResponse { owner }).unwrap();
+                    SystemResult::Ok(ContractResult::Ok(res))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "unknown contract".to_string(),
+                }),
+            }
+        };
+        deps.querier.update_wasm(owners([ADDR1, ADDR1, ADDR2]));
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: Some(PollKind::Cw721 {
+                contract: "nft".to_string(),
+            }),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Votes must name the tokens voted with
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::BallotKindMismatch {});
+
+        let nft_vote = |option: &str, token_ids: &[&str]| ExecuteMsg::NftVote {
+            poll_id: "some_id".to_string(),
+            vote: vec![option.to_string()],
+            token_ids: token_ids.iter().map(|id| id.to_string()).collect(),
+        };
+        for (token_ids, expected) in [
+            (vec![], ContractError::NoVotingPower {}),
+            (vec!["1", "1"], ContractError::DuplicateToken {}),
+            (vec!["1", "3"], ContractError::NotTokenOwner {}),
+        ] {
+            let msg = nft_vote("Juno", &token_ids);
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, expected);
+        }
+
+        // Each token is one vote
+        let msg = nft_vote("Juno", &["1", "2"]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Juno", "2")]
+        );
+
+        // The token 2 changes hands, its new owner can only change its ballot
+        deps.querier.update_wasm(owners([ADDR1, ADDR2, ADDR2]));
+        let msg = nft_vote("Cosmos Hub", &["2", "3"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(poll.total_ballots, 3);
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), Decimal256::percent(200)),
+                ("Juno".to_string(), Decimal256::one()),
+            ]
+        );
+
+        let msg = QueryMsg::TokenVote {
+            poll_id: "some_id".to_string(),
+            token_id: "2".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: TokenVoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.vote,
+            Some(Ballot {
+                options: vec!["Cosmos Hub".to_string()],
+                points: vec![("Cosmos Hub".to_string(), Decimal256::one())],
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_staking_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // ADDR1 delegates 100 tokens to each of the validators, ADDR2 only to val2
+        let delegation = |delegator: &str, validator: &str| FullDelegation {
+            delegator: Addr::unchecked(delegator),
+            validator: validator.to_string(),
+            amount: coin(100, "ustake"),
+            can_redelegate: coin(100, "ustake"),
+            accumulated_rewards: vec![],
+        };
+        deps.querier.update_staking(
+            "ustake",
+            &[],
+            &[
+                delegation(ADDR1, "val1"),
+                delegation(ADDR1, "val2"),
+                delegation(ADDR2, "val2"),
+            ],
+        );
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create two polls, the second one only counting delegations to val1
+        let polls = [("some_id_1", None), ("some_id_2", Some(vec!["val1".to_string()]))];
+        for (poll_id, validators) in polls {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: Some(PollKind::Staking { validators }),
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let vote = |poll_id: &str, option: &str| ExecuteMsg::Vote {
+            poll_id: poll_id.to_string(),
+            vote: vec![option.to_string()],
+            proof: None,
+        };
+        let msg = vote("some_id_1", "Juno");
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id_1"), attr("Juno", "200")]
+        );
+        let res = execute(deps.as_mut(), env.clone(), info, vote("some_id_2", "Juno")).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id_2"), attr("Juno", "100")]
+        );
+        let msg = vote("some_id_2", "Juno");
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::NoVotingPower {});
+
+        // The power a ballot was cast with is taken back when the voter changes it
+        deps.querier.update_staking("ustake", &[], &[delegation(ADDR1, "val1")]);
+        let msg = vote("some_id_1", "Cosmos Hub");
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id_1".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), Decimal256::from_ratio(100u128, 1u128)),
+                ("Juno".to_string(), Decimal256::zero()),
+            ]
+        );
+    }
+
+    // cw-multi-test 0.15 only ships a failing staking module, this one bonds delegated coins and
+    // answers the delegation queries staking polls rely on
+    struct StakeKeeper;
+
+    const STAKES: Map<(Addr, String), Uint128> = Map::new("stakes");
+
+    impl Staking for StakeKeeper {}
+
+    impl Module for StakeKeeper {
+        type ExecT = StakingMsg;
+        type QueryT = StakingQuery;
+        type SudoT = StakingSudo;
+
+        fn execute<ExecC, QueryC>(
+            &self,
+            api: &dyn Api,
+            storage: &mut dyn Storage,
+            router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            block: &BlockInfo,
+            sender: Addr,
+            msg: StakingMsg,
+        ) -> AnyResult<AppResponse>
+        where
+            ExecC: std::fmt::Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+            QueryC: CustomQuery + DeserializeOwned + 'static,
+        {
+            match msg {
+                StakingMsg::Delegate { validator, amount } => {
+                    // the bonded coins leave the delegator's balance
+                    let send = BankMsg::Send {
+                        to_address: "staking".to_string(),
+                        amount: vec![amount.clone()],
+                    };
+                    router.execute(api, storage, block, sender.clone(), send.into())?;
+                    STAKES.update(storage, (sender, validator), |stake| -> StdResult<_> {
+                        Ok(stake.unwrap_or_default() + amount.amount)
+                    })?;
+                    Ok(AppResponse::default())
+                }
+                msg => bail!("Unsupported staking message: {:?}", msg),
+            }
+        }
+
+        fn sudo<ExecC, QueryC>(
+            &self,
+            _api: &dyn Api,
+            _storage: &mut dyn Storage,
+            _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            _block: &BlockInfo,
+            msg: StakingSudo,
+        ) -> AnyResult<AppResponse>
+        where
+            ExecC: std::fmt::Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+            QueryC: CustomQuery + DeserializeOwned + 'static,
+        {
+            bail!("Unsupported staking sudo message: {:?}", msg)
+        }
+
+        fn query(
+            &self,
+            _api: &dyn Api,
+            storage: &dyn Storage,
+            _querier: &dyn Querier,
+            _block: &BlockInfo,
+            request: StakingQuery,
+        ) -> AnyResult<Binary> {
+            match request {
+                StakingQuery::AllDelegations { delegator } => {
+                    let delegator = Addr::unchecked(delegator);
+                    let delegations = STAKES
+                        .prefix(delegator.clone())
+                        .range(storage, None, None, Order::Ascending)
+                        .map(|stake| {
+                            let (validator, amount) = stake?;
+                            Ok(Delegation {
+                                delegator: delegator.clone(),
+                                validator,
+                                amount: coin(amount.u128(), "ustake"),
+                            })
+                        })
+                        .collect::<StdResult<Vec<_>>>()?;
+                    Ok(to_binary(&AllDelegationsResponse { delegations })?)
+                }
+                request => bail!("Unsupported staking query: {:?}", request),
+            }
+        }
+    }
+
+    #[test]
+    fn test_execute_staking_vote_multi_test() {
+        let (addr1, addr2) = (Addr::unchecked(ADDR1), Addr::unchecked(ADDR2));
+        let mut app = AppBuilder::new().with_staking(StakeKeeper).build(|router, _, storage| {
+            for addr in [&addr1, &addr2] {
+                router.bank.init_balance(storage, addr, coins(1000, "ustake")).unwrap();
+            }
+        });
+        let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                addr1.clone(),
+                &InstantiateMsg { admin: None },
+                &[],
+                "polls",
+                None,
+            )
+            .unwrap();
+
+        // ADDR1 bonds 100 tokens to val1 and 50 tokens to val2, ADDR2 bonds nothing
+        for (validator, amount) in [("val1", 100), ("val2", 50)] {
+            let msg = StakingMsg::Delegate {
+                validator: validator.to_string(),
+                amount: coin(amount, "ustake"),
+            };
+            app.execute(addr1.clone(), msg.into()).unwrap();
+        }
+        let balance = app.wrap().query_balance(&addr1, "ustake").unwrap();
+        assert_eq!(balance.amount, Uint128::new(850));
+
+        // Create two polls, the second one only counting delegations to val1
+        let polls = [("some_id_1", None), ("some_id_2", Some(vec!["val1".to_string()]))];
+        for (poll_id, validators) in polls {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: Some(PollKind::Staking { validators }),
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            app.execute_contract(addr1.clone(), contract.clone(), &msg, &[]).unwrap();
+        }
+
+        let vote = |poll_id: &str| ExecuteMsg::Vote {
+            poll_id: poll_id.to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+        let err = app
+            .execute_contract(addr2, contract.clone(), &vote("some_id_1"), &[])
+            .unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoVotingPower {});
+
+        for (poll_id, power) in [("some_id_1", 150u128), ("some_id_2", 100)] {
+            app.execute_contract(addr1.clone(), contract.clone(), &vote(poll_id), &[]).unwrap();
+            let msg = QueryMsg::Results {
+                poll_id: poll_id.to_string(),
+            };
+            let res: ResultsResponse = app.wrap().query_wasm_smart(&contract, &msg).unwrap();
+            assert_eq!(res.options[1].count, Decimal256::from_ratio(power, 1u128));
+        }
+    }
+
+    #[test]
+    fn test_execute_allowlist() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a poll only ADDR1 can vote on, and one without an allowlist
+        let polls = [("some_id_1", Some(vec![ADDR1.to_string()])), ("some_id_2", None)];
+        for (poll_id, voters) in polls {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: None,
+                start: None,
+                end: None,
+                group: None,
+                voters,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let vote = ExecuteMsg::Vote {
+            poll_id: "some_id_1".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), vote.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), vote.clone())
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotEligible {});
+
+        // Only the creator manages the allowlist, of polls created with one
+        let msg = ExecuteMsg::AddVoters {
+            poll_id: "some_id_1".to_string(),
+            voters: vec![ADDR2.to_string()],
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg.clone())
+            .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), vote).unwrap();
+
+        let msg = ExecuteMsg::AddVoters {
+            poll_id: "some_id_2".to_string(),
+            voters: vec![ADDR2.to_string()],
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::NoAllowlist {});
+
+        let msg = QueryMsg::EligibleVoters {
+            poll_id: "some_id_1".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: EligibleVotersResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.voters, vec![ADDR1, ADDR2]);
+
+        let msg = QueryMsg::EligibleVoters {
+            poll_id: "some_id_1".to_string(),
+            start_after: Some(ADDR1.to_string()),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: EligibleVotersResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.voters, vec![ADDR2]);
+
+        // Removed voters can't change their ballot anymore
+        let msg = ExecuteMsg::RemoveVoters {
+            poll_id: "some_id_1".to_string(),
+            voters: vec![ADDR1.to_string()],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id_1".to_string(),
+            vote: vec!["Cosmos Hub".to_string()],
+            proof: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(err, ContractError::NotEligible {});
+    }
+
+    #[test]
+    fn test_execute_merkle_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Tree of ADDR1 with a weight of 5 and ADDR2 with a weight of 2
+        let leaf = |addr: &str, weight: u128| -> [u8; 32] {
+            Sha256::digest(format!("{}{}", addr, weight).as_bytes()).into()
+        };
+        let (leaf1, leaf2) = (leaf(ADDR1, 5), leaf(ADDR2, 2));
+        let mut pair = [leaf1, leaf2];
+        pair.sort_unstable();
+        let root = hex::encode(Sha256::digest(pair.concat()));
+
+        let create_poll = |kind: Option<PollKind>, merkle_root: &str| ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: Some(merkle_root.to_string()),
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), create_poll(None, "root"))
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidMerkleRoot {});
+        let msg = create_poll(Some(PollKind::Score { max_score: 5 }), &root);
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMerklePoll {});
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), create_poll(None, &root))
+            .unwrap();
+
+        let vote = |weight: u128, proof: Option<[u8; 32]>| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: proof.map(|sibling| MerkleProof {
+                weight: Uint128::new(weight),
+                proof: vec![hex::encode(sibling)],
+            }),
+        };
+        for (msg, expected) in [
+            (vote(5, None), ContractError::NotEligible {}),
+            (vote(6, Some(leaf2)), ContractError::InvalidProof {}),
+            (vote(5, Some(leaf1)), ContractError::InvalidProof {}),
+        ] {
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, expected);
+        }
+        // ADDR2 can't use the proof of ADDR1
+        let msg = vote(5, Some(leaf2));
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidProof {});
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), vote(5, Some(leaf2))).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Juno", "5")]
+        );
+
+        // The proven weight is remembered for later ballots
+        let msg = ExecuteMsg::WeightedVote {
+            poll_id: "some_id".to_string(),
+            options: vec![
+                ("Cosmos Hub".to_string(), Decimal::percent(40)),
+                ("Juno".to_string(), Decimal::percent(60)),
+            ],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "vote"),
+                attr("poll_id", "some_id"),
+                attr("Cosmos Hub", "2"),
+                attr("Juno", "3"),
+            ]
+        );
+
+        let msg = QueryMsg::Vote {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.weight, Some(Uint128::new(5)));
+    }
+
+    #[test]
+    fn test_execute_merkle_ranked_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Tree of ADDR1 with a weight of 10^20 and ADDR2 with a weight of 1
+        let weight = 10u128.pow(20);
+        let leaf = |addr: &str, weight: u128| -> [u8; 32] {
+            Sha256::digest(format!("{}{}", addr, weight).as_bytes()).into()
+        };
+        let (leaf1, leaf2) = (leaf(ADDR1, weight), leaf(ADDR2, 1));
+        let mut pair = [leaf1, leaf2];
+        pair.sort_unstable();
+        let root = hex::encode(Sha256::digest(pair.concat()));
+
+        for (poll_id, kind) in [("borda", PollKind::Borda), ("ranked", PollKind::RankedChoice)] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec![
+                    "Cosmos Hub".to_string(),
+                    "Juno".to_string(),
+                    "Osmosis".to_string(),
+                ],
+                kind: Some(kind),
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: Some(root.clone()),
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+            for (voter, ranking, proof) in [
+                (ADDR1, ["Juno", "Osmosis"], (weight, leaf2)),
+                (ADDR2, ["Osmosis", "Juno"], (1, leaf1)),
+            ] {
+                let msg = ExecuteMsg::Vote {
+                    poll_id: poll_id.to_string(),
+                    vote: ranking.iter().map(|option| option.to_string()).collect(),
+                    proof: Some(MerkleProof {
+                        weight: Uint128::new(proof.0),
+                        proof: vec![hex::encode(proof.1)],
+                    }),
+                };
+                let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+            }
+        }
+
+        // Borda points are multiplied by the proven weight
+        let msg = QueryMsg::Poll {
+            poll_id: "borda".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        let votes = |n: u128| Decimal256::from_ratio(n, 1u128);
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), votes(0)),
+                ("Juno".to_string(), votes(2 * weight + 1)),
+                ("Osmosis".to_string(), votes(weight + 2)),
+            ]
+        );
+
+        // Ranked ballots count with the proven weight in the runoff and head to head
+        let msg = QueryMsg::RunoffResults {
+            poll_id: "ranked".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: RunoffResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.rounds[0].tallies[1], ("Juno".to_string(), votes(weight)));
+        assert_eq!(res.winners, vec!["Juno"]);
+
+        let msg = QueryMsg::SchulzeResults {
+            poll_id: "ranked".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: SchulzeResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.pairwise[1][2], votes(weight));
+        assert_eq!(res.pairwise[2][1], votes(1));
+        assert_eq!(res.winners, vec!["Juno"]);
+    }
+
+    #[test]
+    fn test_execute_commit_reveal_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Ballots are committed to for 10 blocks, then revealed for another 10 blocks
+        let height = env.block.height;
+        let create_poll = |end: Option<Expiration>, reveal_end: u64| ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string(), "Juno Cash".to_string()],
+            kind: None,
+            start: None,
+            end,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: Some(Expiration::AtHeight(reveal_end)),
+            hide_results_until_close: None,
+        };
+        for msg in [
+            create_poll(None, height + 20),
+            create_poll(Some(Expiration::AtHeight(height + 10)), height + 10),
+        ] {
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidRevealEnd {});
+        }
+        let msg = create_poll(Some(Expiration::AtHeight(height + 10)), height + 20);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Ballots can only be committed to
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::BallotKindMismatch {});
+
+        let commit = |commitment: String| ExecuteMsg::CommitVote {
+            poll_id: "some_id".to_string(),
+            commitment,
+        };
+        let hash = |option: &str, salt: &str| {
+            let encoded = to_binary(&(vec![option], salt)).unwrap();
+            hex::encode(Sha256::digest(encoded.as_slice()))
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), commit("Juno".to_string()))
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidCommitment {});
+
+        // ADDR1 changes their mind before the end of the poll
+        let msg = commit(hash("Cosmos Hub", "salt1"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = commit(hash("Juno", "salt1"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = commit(hash("Cosmos Hub", "salt2"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let msg = commit(hash("Juno Cash", ""));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("addr3", &[]), msg).unwrap();
+
+        let reveal = |option: &str, salt: &str| ExecuteMsg::RevealVote {
+            poll_id: "some_id".to_string(),
+            vote: vec![option.to_string()],
+            salt: salt.to_string(),
+        };
+        let msg = reveal("Juno", "salt1");
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::RevealNotStarted {});
+
+        // Reveal once the poll has ended
+        let mut revealing = env.clone();
+        revealing.block.height = height + 10;
+        let msg = commit(hash("Juno", "salt3"));
+        let err = execute(deps.as_mut(), revealing.clone(), mock_info("addr3", &[]), msg)
+            .unwrap_err();
+        assert_eq!(err, ContractError::PollExpired {});
+        for (msg, expected) in [
+            (reveal("Cosmos Hub", "salt1"), ContractError::InvalidReveal {}),
+            (reveal("Juno", "salt2"), ContractError::InvalidReveal {}),
+        ] {
+            let err = execute(deps.as_mut(), revealing.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, expected);
+        }
+        let msg = reveal("Juno", "salt1");
+        let res = execute(deps.as_mut(), revealing.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "vote"), attr("poll_id", "some_id"), attr("Juno", "1")]
+        );
+        let msg = reveal("Cosmos Hub", "salt1");
+        let err = execute(deps.as_mut(), revealing.clone(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::NoCommitment {});
+
+        // Part of the option can't be passed off as the salt to reveal another ballot
+        let msg = reveal("Juno", " Cash");
+        let err = execute(deps.as_mut(), revealing.clone(), mock_info("addr3", &[]), msg)
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidReveal {});
+        let msg = reveal("Juno Cash", "");
+        let _res = execute(deps.as_mut(), revealing.clone(), mock_info("addr3", &[]), msg).unwrap();
+
+        let msg = QueryMsg::CommitReveal {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), revealing.clone(), msg).unwrap();
+        let res: CommitRevealResponse = from_binary(&bin).unwrap();
+        assert_eq!(res, CommitRevealResponse { commits: 3, reveals: 2 });
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), revealing, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.poll.unwrap().status, PollStatus::Revealing);
+
+        // ADDR2 missed the reveal phase, their ballot is never counted
+        let mut expired = env;
+        expired.block.height = height + 20;
+        let msg = reveal("Cosmos Hub", "salt2");
+        let err = execute(deps.as_mut(), expired, mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::PollExpired {});
+    }
+
+    #[test]
+    fn test_execute_close_poll_valid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract with ADDR2 as admin
+        let msg = InstantiateMsg { admin: Some(ADDR2.to_string()) };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create two polls as ADDR1
+        for poll_id in ["some_id_1", "some_id_2"] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec![
+                    "Cosmos Hub".to_string(),
+                    "Juno".to_string(),
+                    "Osmosis".to_string(),
+                ],
+                kind: None,
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // The creator can close their poll
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id_1".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "close_poll"), attr("poll_id", "some_id_1")]
+        );
+
+        // The admin can close any poll
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id_2".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // Closing an already closed poll fails
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id_1".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::PollClosed {});
+
+        // The status is exposed through the poll query
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id_1".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.poll.unwrap().status, PollStatus::Closed);
+    }
+
+    #[test]
+    fn test_execute_close_poll_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Close a poll that does not exist
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::PollNotFound {});
+
+        // Create the poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR2 is neither the creator nor the admin
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized);
+
+        // Close the poll as the creator
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Voting on a closed poll fails
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+        let err = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::PollClosed {});
+    }
+
+    #[test]
+    fn test_execute_vote_poll_period() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // A poll that has already ended cannot be created
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: None,
+            end: Some(Expiration::AtHeight(env.block.height)),
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidPollEnd {});
+
+        // Nor a poll which ends before it starts
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: Some(Scheduled::AtHeight(env.block.height + 100)),
+            end: Some(Expiration::AtHeight(env.block.height + 10)),
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidPollStart {});
+
+        // Create a poll open from 10 blocks from now until 100 seconds after that
+        let start_height = env.block.height + 10;
+        let end_time = env.block.time.plus_seconds(100);
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: Some(Scheduled::AtHeight(start_height)),
+            end: Some(Expiration::AtTime(end_time)),
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let vote = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
+        };
+
+        // Too early
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), vote.clone()).unwrap_err();
+        assert_eq!(err, ContractError::PollNotStarted {});
+
+        // Within the bounds
+        env.block.height = start_height;
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), vote.clone()).unwrap();
+
+        // Too late
+        env.block.time = end_time;
+        let err = execute(deps.as_mut(), env, info, vote).unwrap_err();
+        assert_eq!(err, ContractError::PollExpired {});
+    }
+
+
+    #[test]
+    fn test_query_all_polls() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+    
+        let msg = InstantiateMsg{ admin : None};
+
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id_1".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
+            ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a second poll
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id_2".to_string()),
+            question: "What's your colour?".to_string(),
+            options: vec![
+                "Red".to_string(), 
+                "Green".to_string(), 
+                "Blue".to_string()
+            ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: None,
+            order: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 2);
+        assert_eq!(res.polls[0].0, "some_id_1");
+
+    }
+
+    #[test]
+    fn test_query_all_polls_paginated() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create more polls than fit on a single page
+        for _ in 0..35 {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: None,
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: None,
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // The default limit applies when none is given
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: None,
+            order: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 10);
+
+        // The limit is capped
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: Some(100),
+            order: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 30);
+
+        // Ids are ordered as strings, "1", "10", "11", ...
+        let msg = QueryMsg::AllPolls {
+            start_after: Some("10".to_string()),
+            limit: Some(2),
+            order: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["11", "12"]);
+
+        let msg = QueryMsg::AllPolls {
+            start_after: Some("10".to_string()),
+            limit: Some(2),
+            order: Some(SortOrder::Descending),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["1"]);
+    }
+
+    #[test]
+    fn test_query_polls_by_creator() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // ADDR1 creates two polls, ADDR2 creates one
+        for (creator, poll_id) in [(ADDR1, "some_id_1"), (ADDR2, "some_id_2"), (ADDR1, "some_id_3")] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: None,
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(creator, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::PollsByCreator {
+            creator: ADDR1.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["some_id_1", "some_id_3"]);
+
+        let msg = QueryMsg::PollsByCreator {
+            creator: ADDR1.to_string(),
+            start_after: Some("some_id_1".to_string()),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["some_id_3"]);
+    }
+
+    #[test]
+    fn test_query_polls_by_status() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // One open poll, one pending poll and one closed poll
+        for (poll_id, start) in [
+            ("some_id_1", None),
+            ("some_id_2", Some(Scheduled::AtHeight(env.block.height + 1))),
+            ("some_id_3", None),
+        ] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: None,
+                start,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id_3".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-   #[test]
-   fn test_execute_create_poll_valid(){
-    let mut deps = mock_dependencies();
-    let env = mock_env();
-    let info = mock_info(ADDR1, &vec![]);
-    // Instantiate the contract
-    let msg = InstantiateMsg { admin: None };
-    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-    let msg = ExecuteMsg::CreatePoll {
-        poll_id: "some_id".to_string(),
-        question: "What's your favourite Cosmos coin?".to_string(),
-        options: vec![
-            "Cosmos Hub".to_string(),
-            "Juno".to_string(),
-            "Osmosis".to_string(),
-        ],
-    };
-    let res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_eq!(
-        res.attributes,
-        vec![attr("action", "create_poll"), attr("poll_id", "some_id")]
-    )
-   }
+        for (status, expected) in [
+            (PollStatus::Open, "some_id_1"),
+            (PollStatus::Pending, "some_id_2"),
+            (PollStatus::Closed, "some_id_3"),
+        ] {
+            let msg = QueryMsg::PollsByStatus {
+                status,
+                start_after: None,
+                limit: None,
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: PollsByStatusResponse = from_binary(&bin).unwrap();
+            let ids: Vec<_> = res.polls.into_iter().map(|(id, _)| id).collect();
+            assert_eq!(ids, vec![expected]);
+            assert_eq!(res.last_scanned, None);
+        }
 
-   #[test]
-    fn test_execute_create_poll_invalid() {
+        // The limit bounds the polls scanned, the open poll comes first and is filtered out
+        let msg = QueryMsg::PollsByStatus {
+            status: PollStatus::Pending,
+            start_after: None,
+            limit: Some(1),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollsByStatusResponse = from_binary(&bin).unwrap();
+        assert!(res.polls.is_empty());
+        assert_eq!(res.last_scanned, Some("some_id_1".to_string()));
+
+        let msg = QueryMsg::PollsByStatus {
+            status: PollStatus::Pending,
+            start_after: res.last_scanned,
+            limit: Some(1),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollsByStatusResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls[0].0, "some_id_2");
+        assert_eq!(res.last_scanned, Some("some_id_2".to_string()));
+    }
+    #[test]
+    fn test_query_poll() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
         let msg = InstantiateMsg { admin: None };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    
+        // Create a poll
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id".to_string(),
-            question: "What's your favourite number?".to_string(),
+            poll_id: Some("some_id_1".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
             options: vec![
-                "1".to_string(),
-                "2".to_string(),
-                "3".to_string(),
-                "4".to_string(),
-                "5".to_string(),
-                "6".to_string(),
-                "7".to_string(),
-                "8".to_string(),
-                "9".to_string(),
-                "10".to_string(),
-                "11".to_string(),
+                "Cosmos Hub".to_string(),
+                "Juno".to_string(),
+                "Osmosis".to_string(),
             ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
         };
-    
-        // Unwrap error to assert failure
-        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
-        assert_eq!(err, ContractError::TooManyOptions {  })
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id_1".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        // Expect a poll
+        assert!(res.poll.is_some());
     }
+
     #[test]
-    fn test_execute_vote_valid() {
+    fn test_query_results() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
         let msg = InstantiateMsg { admin: None };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // Create the poll
+        // Create a poll
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id".to_string(),
+            poll_id: Some("some_id_1".to_string()),
             question: "What's your favourite Cosmos coin?".to_string(),
             options: vec![
                 "Cosmos Hub".to_string(),
                 "Juno".to_string(),
                 "Osmosis".to_string(),
             ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // Create the vote, first time voting
-        let msg = ExecuteMsg::Vote {
-            poll_id: "some_id".to_string(),
-            vote: "Juno".to_string(),
+        // Nobody voted yet, so there is no winner
+        let msg = QueryMsg::Results {
+            poll_id: "some_id_1".to_string(),
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.total_ballots, 0);
+        assert!(res.winners.is_empty());
+        assert!(!res.tie);
 
-        // Change the vote
-        let msg = ExecuteMsg::Vote {
-            poll_id: "some_id".to_string(),
-            vote: "Osmosis".to_string(),
-        };
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        // Juno and Osmosis get two votes each, Cosmos Hub gets one
+        for (voter, vote) in [
+            ("addr1", "Juno"),
+            ("addr2", "Juno"),
+            ("addr3", "Osmosis"),
+            ("addr4", "Osmosis"),
+            ("addr5", "Cosmos Hub"),
+        ] {
+            let vote_msg = ExecuteMsg::Vote {
+                poll_id: "some_id_1".to_string(),
+                vote: vec![vote.to_string()],
+                proof: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), vote_msg).unwrap();
+        }
 
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, PollStatus::Open);
+        assert_eq!(res.total_ballots, 5);
         assert_eq!(
-            res.attributes,
-            vec![attr("action", "vote"), attr("poll_id", "some_id"),attr("Osmosis", "1")]
-        )
-       
+            res.options,
+            vec![
+                OptionResult {
+                    option: "Cosmos Hub".to_string(),
+                    count: Decimal256::one(),
+                    share: Decimal256::percent(20),
+                    average: Decimal256::percent(20),
+                },
+                OptionResult {
+                    option: "Juno".to_string(),
+                    count: Decimal256::percent(200),
+                    share: Decimal256::percent(40),
+                    average: Decimal256::percent(40),
+                },
+                OptionResult {
+                    option: "Osmosis".to_string(),
+                    count: Decimal256::percent(200),
+                    share: Decimal256::percent(40),
+                    average: Decimal256::percent(40),
+                },
+            ]
+        );
+        assert_eq!(
+            res.ranking,
+            vec![
+                vec!["Juno".to_string(), "Osmosis".to_string()],
+                vec!["Cosmos Hub".to_string()],
+            ]
+        );
+        assert_eq!(res.winners, vec!["Juno", "Osmosis"]);
+        assert!(res.tie);
     }
-    
+
     #[test]
-    fn test_execute_vote_invalid() {
+    fn test_query_hidden_results() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
         let msg = InstantiateMsg { admin: None };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // Create the vote, some_id poll is not created yet.
-        let msg = ExecuteMsg::Vote {
-            poll_id: "some_id".to_string(),
-            vote: "Juno".to_string(),
+        // Create a poll hiding its tallies until it ends
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id_1".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Juno".to_string(), "Osmosis".to_string()],
+            kind: None,
+            start: None,
+            end: Some(Expiration::AtHeight(env.block.height + 10)),
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: Some(true),
         };
-        // Unwrap to assert error
-        let _err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // Create the poll
+        // The vote event doesn't report the tallies either
+        for (voter, vote) in [("addr1", "Juno"), ("addr2", "Juno"), ("addr3", "Osmosis")] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id_1".to_string(),
+                vote: vec![vote.to_string()],
+                proof: None,
+            };
+            let res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+            assert_eq!(res.attributes.len(), 2);
+        }
+
+        // Only the labels and the turnout are known while the poll is open
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id_1".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(poll.total_ballots, 3);
+        assert!(poll.options.iter().all(|(_, count)| count.is_zero()));
+
+        let results_msg = QueryMsg::Results {
+            poll_id: "some_id_1".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), results_msg.clone()).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.total_ballots, 3);
+        assert_eq!(res.options.len(), 2);
+        assert!(res.options.iter().all(|o| o.count.is_zero()));
+        assert!(res.ranking.is_empty());
+        assert!(res.winners.is_empty());
+
+        let ballots_msg = QueryMsg::Ballots {
+            poll_id: "some_id_1".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), ballots_msg.clone()).unwrap();
+        assert!(from_binary::<BallotsResponse>(&bin).unwrap().ballots.is_empty());
+
+        // Neither can the ballots be looked up voter by voter
+        let vote_msg = QueryMsg::Vote {
+            poll_id: "some_id_1".to_string(),
+            address: "addr1".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), vote_msg.clone()).unwrap();
+        assert_eq!(from_binary::<VoteResponse>(&bin).unwrap().vote, None);
+
+        let votes_msg = QueryMsg::VotesByVoter {
+            address: "addr1".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), votes_msg.clone()).unwrap();
+        assert!(from_binary::<VotesByVoterResponse>(&bin).unwrap().votes.is_empty());
+
+        // Everything is revealed once the poll has expired
+        let mut expired = env;
+        expired.block.height += 10;
+        let bin = query(deps.as_ref(), expired.clone(), msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(poll.options[0], ("Juno".to_string(), Decimal256::percent(200)));
+
+        let bin = query(deps.as_ref(), expired.clone(), results_msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, PollStatus::Expired);
+        assert_eq!(res.winners, vec!["Juno"]);
+
+        let bin = query(deps.as_ref(), expired.clone(), ballots_msg).unwrap();
+        assert_eq!(from_binary::<BallotsResponse>(&bin).unwrap().ballots.len(), 3);
+
+        let bin = query(deps.as_ref(), expired.clone(), vote_msg).unwrap();
+        assert!(from_binary::<VoteResponse>(&bin).unwrap().vote.is_some());
+
+        let bin = query(deps.as_ref(), expired, votes_msg).unwrap();
+        assert_eq!(from_binary::<VotesByVoterResponse>(&bin).unwrap().votes.len(), 1);
+    }
+
+    #[test]
+    fn test_query_runoff_results() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a ranked choice poll
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id".to_string(),
-            question: "What's your favourite Cosmos coin?".to_string(),
+            poll_id: Some("some_id_1".to_string()),
+            question: "Rank your favourite Cosmos coins".to_string(),
             options: vec![
                 "Cosmos Hub".to_string(),
                 "Juno".to_string(),
                 "Osmosis".to_string(),
             ],
+            kind: Some(PollKind::RankedChoice),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // Vote on a now existing poll but the option "DVPN" does not exist
-        let msg = ExecuteMsg::Vote {
-            poll_id: "some_id".to_string(),
-            vote: "DVPN".to_string(),
+        for (voter, ranking) in [
+            ("addr1", vec!["Cosmos Hub", "Juno"]),
+            ("addr2", vec!["Cosmos Hub", "Osmosis"]),
+            ("addr3", vec!["Juno", "Osmosis"]),
+            ("addr4", vec!["Osmosis", "Juno"]),
+            // addr5 changes their ranking
+            ("addr5", vec!["Juno"]),
+            ("addr5", vec!["Osmosis", "Juno", "Cosmos Hub"]),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id_1".to_string(),
+                vote: ranking.into_iter().map(String::from).collect(),
+                proof: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        // Only first preferences are counted on the poll
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id_1".to_string(),
         };
-        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
-        assert_eq!(err, ContractError::VoteOptionNotFound{})
-    }
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let poll = from_binary::<PollResponse>(&bin).unwrap().poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![
+                ("Cosmos Hub".to_string(), Decimal256::percent(200)),
+                ("Juno".to_string(), Decimal256::one()),
+                ("Osmosis".to_string(), Decimal256::percent(200)),
+            ]
+        );
 
+        // Juno is eliminated first, its ballot moves on to Osmosis which then has a majority
+        let msg = QueryMsg::RunoffResults {
+            poll_id: "some_id_1".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: RunoffResultsResponse = from_binary(&bin).unwrap();
+        let votes = |n: u128| Decimal256::from_ratio(n, 1u128);
+        assert_eq!(
+            res.rounds,
+            vec![
+                RunoffRound {
+                    tallies: vec![
+                        ("Cosmos Hub".to_string(), votes(2)),
+                        ("Juno".to_string(), votes(1)),
+                        ("Osmosis".to_string(), votes(2)),
+                    ],
+                    eliminated: vec!["Juno".to_string()],
+                },
+                RunoffRound {
+                    tallies: vec![
+                        ("Cosmos Hub".to_string(), votes(2)),
+                        ("Osmosis".to_string(), votes(3)),
+                    ],
+                    eliminated: vec![],
+                },
+            ]
+        );
+        assert_eq!(res.winners, vec!["Osmosis"]);
+        assert!(!res.tie);
+    }
 
     #[test]
-    fn test_query_all_polls() {
+    fn test_query_schulze_results() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
-    
-        let msg = InstantiateMsg{ admin : None};
-
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create a ranked choice poll
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_1".to_string(),
-            question: "What's your favourite Cosmos coin?".to_string(),
+            poll_id: Some("some_id_1".to_string()),
+            question: "Rank your favourite Cosmos coins".to_string(),
             options: vec![
                 "Cosmos Hub".to_string(),
                 "Juno".to_string(),
                 "Osmosis".to_string(),
             ],
+            kind: Some(PollKind::RankedChoice),
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // Create a second poll
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_2".to_string(),
-            question: "What's your colour?".to_string(),
-            options: vec![
-                "Red".to_string(), 
-                "Green".to_string(), 
-                "Blue".to_string()
-            ],
+        for (voter, ranking) in [
+            ("addr1", vec!["Cosmos Hub", "Juno", "Osmosis"]),
+            ("addr2", vec!["Cosmos Hub", "Juno", "Osmosis"]),
+            ("addr3", vec!["Cosmos Hub", "Juno"]),
+            ("addr4", vec!["Juno", "Osmosis", "Cosmos Hub"]),
+            ("addr5", vec!["Juno", "Osmosis"]),
+            // addr6 changes their ranking, the first one must not be counted
+            ("addr6", vec!["Juno", "Cosmos Hub"]),
+            ("addr6", vec!["Osmosis", "Cosmos Hub", "Juno"]),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: "some_id_1".to_string(),
+                vote: ranking.into_iter().map(String::from).collect(),
+                proof: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        // Cosmos Hub only ties Osmosis head to head, but beats it through Juno
+        let msg = QueryMsg::SchulzeResults {
+            poll_id: "some_id_1".to_string(),
         };
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        let msg = QueryMsg::AllPolls {};
         let bin = query(deps.as_ref(), env, msg).unwrap();
-        let res: AllPollsResponse = from_binary(&bin).unwrap();
-        assert_eq!(res.polls.len(), 2);
-
+        let res: SchulzeResultsResponse = from_binary(&bin).unwrap();
+        let pairwise: Vec<Vec<u128>> = vec![vec![0, 4, 3], vec![2, 0, 5], vec![3, 1, 0]];
+        let pairwise: Vec<Vec<Decimal256>> = pairwise
+            .into_iter()
+            .map(|row| row.into_iter().map(|n| Decimal256::from_ratio(n, 1u128)).collect())
+            .collect();
+        assert_eq!(res.pairwise, pairwise);
+        assert_eq!(
+            res.ranking,
+            vec![
+                vec!["Cosmos Hub".to_string()],
+                vec!["Juno".to_string()],
+                vec!["Osmosis".to_string()],
+            ]
+        );
+        assert_eq!(res.winners, vec!["Cosmos Hub"]);
+        assert!(!res.tie);
     }
+
     #[test]
-    fn test_query_poll() {
+    fn test_query_poll_status() {
         let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
         let msg = InstantiateMsg { admin: None };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-    
-        // Create a poll
+
+        // Create a poll open between the next two blocks
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_1".to_string(),
+            poll_id: Some("some_id_1".to_string()),
             question: "What's your favourite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            kind: None,
+            start: Some(Scheduled::AtHeight(env.block.height + 1)),
+            end: Some(Expiration::AtHeight(env.block.height + 2)),
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
         let msg = QueryMsg::Poll {
             poll_id: "some_id_1".to_string(),
         };
-        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
-        let res: PollResponse = from_binary(&bin).unwrap();
-        // Expect a poll
-        assert!(res.poll.is_some());
+        for expected in [PollStatus::Pending, PollStatus::Open, PollStatus::Expired] {
+            let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+            let res: PollResponse = from_binary(&bin).unwrap();
+            assert_eq!(res.poll.unwrap().status, expected);
+            env.block.height += 1;
+        }
     }
     #[test]
     fn test_query_vote() {
@@ -430,20 +4566,29 @@ mod tests {
 
         // Create a poll
         let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_1".to_string(),
+            poll_id: Some("some_id_1".to_string()),
             question: "What's your favourite Cosmos coin?".to_string(),
             options: vec![
                 "Cosmos Hub".to_string(),
                 "Juno".to_string(),
                 "Osmosis".to_string(),
             ],
+            kind: None,
+            start: None,
+            end: None,
+            group: None,
+            voters: None,
+            merkle_root: None,
+            reveal_end: None,
+            hide_results_until_close: None,
         };
         let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
         // Create a vote
         let msg = ExecuteMsg::Vote {
             poll_id: "some_id_1".to_string(),
-            vote: "Juno".to_string(),
+            vote: vec!["Juno".to_string()],
+            proof: None,
         };
         let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
         // Query for a vote that exists
@@ -465,11 +4610,174 @@ mod tests {
         // Expect the vote to not exist
         assert!(res.vote.is_none());
     }
+
+    #[test]
+    fn test_query_ballots() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create two polls
+        for poll_id in ["some_id_1", "some_id_2"] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: None,
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // ADDR1 and ADDR2 vote on the first poll, ADDR1 also on the second one
+        for (voter, poll_id, vote) in [
+            (ADDR1, "some_id_1", "Juno"),
+            (ADDR2, "some_id_1", "Cosmos Hub"),
+            (ADDR1, "some_id_2", "Cosmos Hub"),
+        ] {
+            let msg = ExecuteMsg::Vote {
+                poll_id: poll_id.to_string(),
+                vote: vec![vote.to_string()],
+                proof: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::Ballots {
+            poll_id: "some_id_1".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: BallotsResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.ballots,
+            vec![
+                (
+                    ADDR1.to_string(),
+                    Ballot {
+                        options: vec!["Juno".to_string()],
+                        points: vec![("Juno".to_string(), Decimal256::one())],
+                    }
+                ),
+                (
+                    ADDR2.to_string(),
+                    Ballot {
+                        options: vec!["Cosmos Hub".to_string()],
+                        points: vec![("Cosmos Hub".to_string(), Decimal256::one())],
+                    }
+                ),
+            ]
+        );
+
+        let msg = QueryMsg::Ballots {
+            poll_id: "some_id_1".to_string(),
+            start_after: Some(ADDR1.to_string()),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: BallotsResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.ballots,
+            vec![(
+                ADDR2.to_string(),
+                Ballot {
+                    options: vec!["Cosmos Hub".to_string()],
+                    points: vec![("Cosmos Hub".to_string(), Decimal256::one())],
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_query_votes_by_voter() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        // Instantiate the contract
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Create two polls and vote on both as ADDR1, and on the first one as ADDR2
+        for poll_id in ["some_id_1", "some_id_2"] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                kind: None,
+                start: None,
+                end: None,
+                group: None,
+                voters: None,
+                merkle_root: None,
+                reveal_end: None,
+                hide_results_until_close: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            let msg = ExecuteMsg::Vote {
+                poll_id: poll_id.to_string(),
+                vote: vec!["Juno".to_string()],
+                proof: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id_1".to_string(),
+            vote: vec!["Cosmos Hub".to_string()],
+            proof: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let msg = QueryMsg::VotesByVoter {
+            address: ADDR1.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VotesByVoterResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.votes.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["some_id_1", "some_id_2"]);
+
+        let msg = QueryMsg::VotesByVoter {
+            address: ADDR1.to_string(),
+            start_after: Some("some_id_1".to_string()),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VotesByVoterResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.votes,
+            vec![(
+                "some_id_2".to_string(),
+                Ballot {
+                    options: vec!["Juno".to_string()],
+                    points: vec![("Juno".to_string(), Decimal256::one())],
+                }
+            )]
+        );
+
+        // An invalid address is reported as an error instead of panicking
+        let msg = QueryMsg::VotesByVoter {
+            address: "".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let _err = query(deps.as_ref(), env, msg).unwrap_err();
+    }
     #[test]
     fn test_query_config() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let info = mock_info(ADDR1, &[]);
         // Instantiate the contract
         let msg = InstantiateMsg { admin: None };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -0,0 +1,21 @@
+//! The parts of the cw4 group interface this contract talks to, wire compatible with the `cw4`
+//! crate
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw4QueryMsg {
+    /// Weight of `addr` in the group, at the given height if set
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MemberResponse {
+    /// Unset if `addr` is not a member
+    pub weight: Option<u64>,
+}
@@ -1,17 +1,132 @@
 use cosmwasm_std::StdError;
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
     #[error("Unauthorized")]
     Unauthorized,
     #[error("Too many poll options")]
     TooManyOptions {},
+    #[error("Poll options must be distinct")]
+    DuplicatePollOption {},
     #[error("Poll not found")]
     PollNotFound {},
 
+    #[error("Poll already exists")]
+    PollAlreadyExists {},
+
     #[error("Vote option not found")]
     VoteOptionNotFound {},
+
+    #[error("No vote option given")]
+    EmptyVote {},
+
+    #[error("Too many vote options chosen")]
+    TooManyChoices {},
+
+    #[error("Vote option chosen more than once")]
+    DuplicateVoteOption {},
+
+    #[error("max_choices must be between 1 and the number of poll options")]
+    InvalidMaxChoices {},
+
+    #[error("Governance polls come with their own options")]
+    GovernanceOptions {},
+
+    #[error("Thresholds must be between 0 and 1")]
+    InvalidThreshold {},
+
+    #[error("Quorum must be at least one vote")]
+    InvalidQuorum {},
+
+    #[error("Score is above the poll's max_score")]
+    ScoreTooHigh {},
+
+    #[error("Vote weights must be positive and add up to 1")]
+    InvalidWeights {},
+
+    #[error("Not enough voting credits")]
+    InsufficientCredits {},
+
+    #[error("Ballot does not match the poll kind")]
+    BallotKindMismatch {},
+
+    #[error("Poll is closed")]
+    PollClosed {},
+
+    #[error("Poll has not started yet")]
+    PollNotStarted {},
+
+    #[error("Poll has expired")]
+    PollExpired {},
+
+    #[error("Poll end must be in the future")]
+    InvalidPollEnd {},
+
+    #[error("Poll must start before it ends")]
+    InvalidPollStart {},
+
+    #[error("Poll has not ended yet")]
+    PollNotEnded {},
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("Token not accepted by this poll")]
+    InvalidToken {},
+
+    #[error("No voting power")]
+    NoVotingPower {},
+
+    #[error("Not a member of the poll's group")]
+    NotMember {},
+
+    #[error("Score, quadratic, token and staking polls can't be restricted to a group")]
+    InvalidGroupPoll {},
+
+    #[error("Not the owner of the token")]
+    NotTokenOwner {},
+
+    #[error("Token listed more than once")]
+    DuplicateToken {},
+
+    #[error("Not eligible to vote on this poll")]
+    NotEligible {},
+
+    #[error("Poll has no allowlist")]
+    NoAllowlist {},
+
+    #[error("Invalid merkle root")]
+    InvalidMerkleRoot {},
+
+    #[error("Invalid merkle proof")]
+    InvalidProof {},
+
+    #[error("Score, quadratic, token and staking polls can't have a merkle root")]
+    InvalidMerklePoll {},
+
+    #[error("Score, quadratic, token, staking and merkle polls can't be commit-reveal")]
+    InvalidCommitRevealPoll {},
+
+    #[error("Reveal end must come after the poll end")]
+    InvalidRevealEnd {},
+
+    #[error("Commitment must be a hex encoded sha256 hash")]
+    InvalidCommitment {},
+
+    #[error("No ballot committed to")]
+    NoCommitment {},
+
+    #[error("Revealed ballot does not match the commitment")]
+    InvalidReveal {},
+
+    #[error("Ballots can't be revealed before the poll end")]
+    RevealNotStarted {},
 }
@@ -1,6 +1,11 @@
 pub mod contract;
+pub mod cw20;
+pub mod cw4;
+pub mod cw721;
 mod error;
+mod merkle;
 pub mod msg;
 pub mod state;
+mod tally;
 
 pub use crate::error::ContractError;
@@ -0,0 +1,36 @@
+//! Merkle proofs of (address, weight) leaves, built the same way as the cw20-merkle-airdrop trees
+
+use cosmwasm_std::Uint128;
+use sha2::{Digest, Sha256};
+
+use crate::ContractError;
+
+/// Decodes a hex encoded sha256 merkle root
+pub fn decode_root(root: &str) -> Result<[u8; 32], ContractError> {
+    let mut buf = [0u8; 32];
+    hex::decode_to_slice(root, &mut buf).map_err(|_| ContractError::InvalidMerkleRoot {})?;
+    Ok(buf)
+}
+
+/// Checks that `proof` leads from the leaf of `address` and `weight` to `root`. Leaves are the
+/// sha256 of the address followed by the weight, every step hashes the sorted pair of hashes.
+pub fn verify(
+    root: &str,
+    address: &str,
+    weight: Uint128,
+    proof: &[String],
+) -> Result<(), ContractError> {
+    let mut hash: [u8; 32] = Sha256::digest(format!("{}{}", address, weight).as_bytes()).into();
+    for step in proof {
+        let mut sibling = [0u8; 32];
+        hex::decode_to_slice(step, &mut sibling).map_err(|_| ContractError::InvalidProof {})?;
+        let mut pair = [hash, sibling];
+        pair.sort_unstable();
+        hash = Sha256::digest(pair.concat()).into();
+    }
+
+    if decode_root(root)? != hash {
+        return Err(ContractError::InvalidProof {});
+    }
+    Ok(())
+}
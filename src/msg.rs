@@ -1,7 +1,10 @@
+use cosmwasm_std::{Decimal, Decimal256, Order, Uint128};
+use cw_utils::{Expiration, Scheduled};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{Poll, Ballot};
+use crate::cw20::Cw20ReceiveMsg;
+use crate::state::{Poll, Ballot, GovernanceOutcome, PollKind, PollStatus};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -13,13 +16,116 @@ pub struct InstantiateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     CreatePoll { 
-        poll_id: String,
+        /// Generated from an increasing counter if unset
+        poll_id: Option<String>,
         question: String,
+        /// Must be left empty for governance polls
         options: Vec<String>,
+        /// Single choice if unset
+        kind: Option<PollKind>,
+        /// Votes are rejected until this height or time is reached. Open right away if unset.
+        start: Option<Scheduled>,
+        /// Votes are rejected once this height or time is reached. Never expires if unset.
+        end: Option<Expiration>,
+        /// cw4 group contract restricting the poll to its members, weighted by their weight in the
+        /// group at the poll's creation. Not supported by score, quadratic, token and staking
+        /// polls.
+        group: Option<String>,
+        /// Restricts the poll to these addresses, the creator can change them afterwards
+        voters: Option<Vec<String>>,
+        /// Restricts the poll to the (address, weight) leaves of this hex encoded sha256 merkle
+        /// root, weighted by their weight. Not supported by score, quadratic, token and staking
+        /// polls.
+        merkle_root: Option<String>,
+        /// Makes the poll a commit-reveal one, ballots committed to until `end` are revealed until
+        /// `reveal_end`. Unrevealed ballots are not counted. Not supported by score, quadratic,
+        /// token, staking and merkle polls.
+        reveal_end: Option<Expiration>,
+        /// Hides the tallies and ballots until the poll has expired or is closed, false if unset
+        hide_results_until_close: Option<bool>,
     },
     Vote {
         poll_id: String,
-        vote: String,
+        /// A single option, unless the poll allows choosing more. Ordered by preference for
+        /// ranked choice and Borda polls.
+        vote: Vec<String>,
+        /// Required for the first ballot on a poll with a merkle root, the proven weight is
+        /// remembered for later ballots
+        proof: Option<MerkleProof>,
+    },
+    /// Split a single vote over several options of a single choice poll, like `MsgVoteWeighted`
+    /// of x/gov. Weights must add up to 1.
+    WeightedVote {
+        poll_id: String,
+        options: Vec<(String, Decimal)>,
+    },
+    /// Commit to a ballot of a commit-reveal poll with the hex encoded sha256 of the compact JSON
+    /// array of its options and a secret salt, like `[["Juno","Osmosis"],"salt"]`. Committing
+    /// again replaces the commitment.
+    CommitVote {
+        poll_id: String,
+        commitment: String,
+    },
+    /// Reveal the ballot committed to once voting has ended, like `ExecuteMsg::Vote`
+    RevealVote {
+        poll_id: String,
+        vote: Vec<String>,
+        salt: String,
+    },
+    /// Vote on a cw721 poll with each of the `token_ids` owned by the sender
+    NftVote {
+        poll_id: String,
+        vote: Vec<String>,
+        token_ids: Vec<String>,
+    },
+    /// Vote on a score poll, options left out get a score of 0
+    ScoreVote {
+        poll_id: String,
+        scores: Vec<(String, u64)>,
+    },
+    /// Vote on a quadratic poll, casting n votes on an option costs n² credits.
+    /// Sending it again reallocates the voter's credits.
+    QuadraticVote {
+        poll_id: String,
+        votes: Vec<(String, u64)>,
+    },
+    ClosePoll {
+        poll_id: String,
+    },
+    /// Allow more addresses to vote on a poll created with an allowlist
+    AddVoters {
+        poll_id: String,
+        voters: Vec<String>,
+    },
+    /// Disallow addresses from voting on a poll created with an allowlist, the ballots they have
+    /// already cast keep being counted
+    RemoveVoters {
+        poll_id: String,
+        voters: Vec<String>,
+    },
+    /// Vote on a cw20 poll by sending tokens with the `Send` message of its token contract,
+    /// with a `ReceiveMsg` as the embedded message
+    Receive(Cw20ReceiveMsg),
+    /// Take back the tokens locked with a ballot of a native token or cw20 poll once it has ended
+    Withdraw {
+        poll_id: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MerkleProof {
+    pub weight: Uint128,
+    /// Hex encoded sibling hashes from the voter's leaf up to the root
+    pub proof: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    /// Lock the sent tokens with a ballot, like `ExecuteMsg::Vote`
+    Vote {
+        poll_id: String,
+        vote: Vec<String>,
     },
 }
 
@@ -27,22 +133,127 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Poll { poll_id: String },
-    AllPolls{},
+    Results { poll_id: String },
+    /// Instant-runoff results of a ranked choice poll
+    RunoffResults { poll_id: String },
+    /// Schulze method results of a ranked choice poll
+    SchulzeResults { poll_id: String },
+    AllPolls {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<SortOrder>,
+    },
+    /// The ballot is left out while the poll hides its results
     Vote{
         poll_id: String, 
         address: String,
     },
+    /// Number of committed and revealed ballots of a commit-reveal poll
+    CommitReveal { poll_id: String },
+    /// Ballot of a token in a cw721 poll, left out while the poll hides its results
+    TokenVote {
+        poll_id: String,
+        token_id: String,
+    },
+    /// Empty while the poll hides its results
+    Ballots {
+        poll_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Ballots of polls hiding their results are left out, so a page may hold fewer than `limit`
+    VotesByVoter {
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Addresses allowed to vote on a poll with an allowlist
+    EligibleVoters {
+        poll_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    PollsByCreator {
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Only open and closed polls are indexed, the other statuses are filtered out of the polls
+    /// stored as open. At most `limit` of those are scanned per page, continue from `last_scanned`.
+    PollsByStatus {
+        status: PollStatus,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     Config {},
 }
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl From<SortOrder> for Order {
+    fn from(order: SortOrder) -> Self {
+        match order {
+            SortOrder::Ascending => Order::Ascending,
+            SortOrder::Descending => Order::Descending,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct AllPollsResponse{
-    pub polls : Vec<Poll>,
+    pub polls : Vec<(String, Poll)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollsByStatusResponse {
+    pub polls: Vec<(String, Poll)>,
+    /// Id of the last poll scanned, the page may hold fewer than `limit` polls even if more
+    /// follow. None once there are no more polls to scan.
+    pub last_scanned: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct VoteResponse {
     pub vote: Option<Ballot>,
+    /// Credits the voter has left to spend on a quadratic poll
+    pub remaining_credits: Option<u64>,
+    /// Tokens the voter has locked in a native token or cw20 poll and not withdrawn yet
+    pub locked: Option<Uint128>,
+    /// Weight the voter has proven on a poll with a merkle root
+    pub weight: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CommitRevealResponse {
+    pub commits: u64,
+    pub reveals: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenVoteResponse {
+    pub vote: Option<Ballot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VotesByVoterResponse {
+    /// (poll_id, ballot) pairs
+    pub votes: Vec<(String, Ballot)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct EligibleVotersResponse {
+    pub voters: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BallotsResponse {
+    /// (voter address, ballot) pairs
+    pub ballots: Vec<(String, Ballot)>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -50,6 +261,67 @@ pub struct VoteResponse {
 pub struct PollResponse{
     pub poll: Option<Poll>
 }
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ResultsResponse {
+    pub status: PollStatus,
+    pub total_ballots: u64,
+    pub options: Vec<OptionResult>,
+    /// Options from most to least votes or points, options grouped together are tied
+    pub ranking: Vec<Vec<String>>,
+    /// Options with the most votes, more than one in case of a tie. Empty if nobody voted.
+    pub winners: Vec<String>,
+    pub tie: bool,
+    /// Outcome of a governance poll. Final once the poll is closed, based on the current tallies
+    /// until then.
+    pub outcome: Option<GovernanceOutcome>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct OptionResult {
+    pub option: String,
+    /// Votes, or points for Borda and score polls
+    pub count: Decimal256,
+    /// Share of all votes or points
+    pub share: Decimal256,
+    /// Votes or points per ballot. Same as the share for single choice polls, the approval
+    /// rate for multiple choice polls and the average score for score polls.
+    pub average: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RunoffResultsResponse {
+    pub status: PollStatus,
+    pub rounds: Vec<RunoffRound>,
+    /// Single winner, unless the last options standing were tied. Empty if nobody voted.
+    pub winners: Vec<String>,
+    pub tie: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RunoffRound {
+    /// Votes of the options still in the race
+    pub tallies: Vec<(String, Decimal256)>,
+    /// Options dropped at the end of this round
+    pub eliminated: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SchulzeResultsResponse {
+    pub status: PollStatus,
+    /// Options from most to least preferred, options grouped together are tied
+    pub ranking: Vec<Vec<String>>,
+    /// Options no other option beats. Empty if nobody voted.
+    pub winners: Vec<String>,
+    pub tie: bool,
+    /// pairwise[i][j] counts the votes preferring option i over option j, in poll option order
+    pub pairwise: Vec<Vec<Decimal256>>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ConfigResponse{
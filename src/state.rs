@@ -1,18 +1,229 @@
+use std::fmt;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, BlockInfo, Decimal, Decimal256, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, KeyDeserialize, Map, MultiIndex};
+use cw_utils::{Expiration, Scheduled};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Poll {
     pub creator: Addr,
     pub question: String,
-    pub options: Vec<(String, u64)>
+    /// Votes of each option, or the sum of their points for Borda and score polls. Decimal so
+    /// that weighted votes can split a single vote over several options, 256 bits wide to fit the
+    /// base units of token polls.
+    pub options: Vec<(String, Decimal256)>,
+    pub kind: PollKind,
+    /// Number of voters, a voter changing their ballot is only counted once
+    pub total_ballots: u64,
+    pub status: PollStatus,
+    pub start: Option<Scheduled>,
+    pub end: Expiration,
+    /// Outcome of a governance poll, set once it is closed
+    pub outcome: Option<GovernanceOutcome>,
+    /// Height at which the voting power of snapshot and group polls is read, the poll's creation
+    /// height
+    pub snapshot_height: Option<u64>,
+    /// cw4 group the voters must be members of, each ballot counts as many times as the voter's
+    /// weight in the group
+    pub group: Option<Addr>,
+    /// Only the addresses in `VOTERS` can vote
+    pub allowlist: bool,
+    /// Hex encoded sha256 root of the merkle tree of the eligible (address, weight) pairs, each
+    /// ballot counts as many times as the voter's weight
+    pub merkle_root: Option<String>,
+    /// Makes the poll a commit-reveal one, voters commit to their ballot until `end` and reveal
+    /// it until `reveal_end`. Only revealed ballots are counted, commitments left unrevealed are
+    /// discarded.
+    pub reveal_end: Option<Expiration>,
+    /// Number of voters who committed to a ballot of a commit-reveal poll
+    pub commits: u64,
+    /// Queries report zero counts for every option and leave out the ballots until the poll has
+    /// expired or is closed, only the number of ballots is known
+    pub hide_results_until_close: bool,
+}
+
+impl Poll {
+    /// Resolves the status of the poll at the given block, taking its start and end bounds
+    /// into account. Only `Open` and `Closed` are ever stored, the rest is derived.
+    pub fn status_at(&self, block: &BlockInfo) -> PollStatus {
+        if self.status == PollStatus::Closed {
+            return PollStatus::Closed;
+        }
+        if let Some(start) = self.start {
+            if !start.is_triggered(block) {
+                return PollStatus::Pending;
+            }
+        }
+        if self.end.is_expired(block) {
+            return match self.reveal_end {
+                Some(reveal_end) if !reveal_end.is_expired(block) => PollStatus::Revealing,
+                _ => PollStatus::Expired,
+            };
+        }
+        PollStatus::Open
+    }
+
+    pub fn results_hidden_at(&self, block: &BlockInfo) -> bool {
+        self.hide_results_until_close
+            && !matches!(self.status_at(block), PollStatus::Expired | PollStatus::Closed)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollKind {
+    /// Voters pick exactly one option, or split their vote over several options with a
+    /// weighted vote
+    SingleChoice,
+    /// Voters pick up to `max_choices` distinct options, each of them gets one vote
+    MultipleChoice { max_choices: u32 },
+    /// Voters rank any number of options, results are computed by instant-runoff.
+    /// Only first preferences are counted in `Poll.options`.
+    RankedChoice,
+    /// Voters rank any number of options, with n options the first one gets n - 1 points,
+    /// the second one n - 2 and so on. Unranked options get nothing.
+    Borda,
+    /// Voters give each option a score between 0 and `max_score`
+    Score { max_score: u64 },
+    /// Voters spread votes over the options, casting n votes on an option costs n² of their
+    /// `credits`
+    Quadratic { credits: u64 },
+    /// Chain governance style poll with the `GOVERNANCE_OPTIONS`, voted like a single choice poll.
+    /// It passes if at least `quorum` votes were cast, no more than `veto_threshold` of them
+    /// are no_with_veto and more than `threshold` of the non abstaining ones are yes.
+    /// Unlike x/gov the quorum is an absolute number of votes, not a fraction of them.
+    Governance {
+        quorum: Uint128,
+        threshold: Decimal,
+        veto_threshold: Decimal,
+    },
+    /// Voters lock coins of `denom` with their ballot, each coin is one vote on the single
+    /// option they pick. The coins can be withdrawn once the poll has ended.
+    NativeToken { denom: String },
+    /// Voters send tokens of the cw20 `token` contract along with their ballot, through its
+    /// `Send` message, each token is one vote on the single option they pick. The tokens can be
+    /// withdrawn once the poll has ended.
+    Cw20 { token: String },
+    /// Voters vote with their balance of the cw20 `token` contract at the height the poll was
+    /// created, on the single option they pick. The contract must answer `BalanceAt` queries.
+    Cw20Snapshot { token: String },
+    /// Holders of tokens of the cw721 `contract` vote with `ExecuteMsg::NftVote`, each token is
+    /// one vote on the single option they pick. A token votes once, its new owner can only
+    /// change its ballot.
+    Cw721 { contract: String },
+    /// Voters vote with the tokens they have bonded when casting their ballot, only counting
+    /// delegations to `validators` if set, on the single option they pick. The ballot's points
+    /// record the power it was cast with.
+    Staking { validators: Option<Vec<String>> },
+}
+
+pub const GOVERNANCE_OPTIONS: [&str; 4] = ["yes", "no", "abstain", "no_with_veto"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernanceOutcome {
+    Passed,
+    Rejected,
+    RejectedWithVeto,
+    QuorumNotReached,
+}
+
+impl fmt::Display for GovernanceOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GovernanceOutcome::Passed => write!(f, "passed"),
+            GovernanceOutcome::Rejected => write!(f, "rejected"),
+            GovernanceOutcome::RejectedWithVeto => write!(f, "rejected_with_veto"),
+            GovernanceOutcome::QuorumNotReached => write!(f, "quorum_not_reached"),
+        }
+    }
+}
+
+impl PollKind {
+    pub fn max_choices(&self, options: usize) -> usize {
+        match self {
+            PollKind::SingleChoice
+            | PollKind::Governance { .. }
+            | PollKind::NativeToken { .. }
+            | PollKind::Cw20 { .. }
+            | PollKind::Cw20Snapshot { .. }
+            | PollKind::Cw721 { .. }
+            | PollKind::Staking { .. } => 1,
+            PollKind::MultipleChoice { max_choices } => *max_choices as usize,
+            _ => options,
+        }
+    }
+
+    /// Points a ballot picking `choices` adds to `Poll.options`, for polls with `options` options.
+    /// Score and quadratic ballots carry their points themselves.
+    pub fn points(&self, options: usize, choices: &[String]) -> Vec<(String, Decimal256)> {
+        match self {
+            PollKind::SingleChoice
+            | PollKind::MultipleChoice { .. }
+            | PollKind::Governance { .. }
+            | PollKind::NativeToken { .. }
+            | PollKind::Cw20 { .. }
+            | PollKind::Cw20Snapshot { .. }
+            | PollKind::Cw721 { .. }
+            | PollKind::Staking { .. } => {
+                choices.iter().map(|choice| (choice.clone(), Decimal256::one())).collect()
+            }
+            PollKind::RankedChoice => choices
+                .iter()
+                .take(1)
+                .map(|choice| (choice.clone(), Decimal256::one()))
+                .collect(),
+            PollKind::Borda => choices
+                .iter()
+                .enumerate()
+                .map(|(rank, choice)| {
+                    (choice.clone(), Decimal256::from_ratio((options - 1 - rank) as u128, 1u128))
+                })
+                .filter(|(_, points)| !points.is_zero())
+                .collect(),
+            PollKind::Score { .. } | PollKind::Quadratic { .. } => vec![],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollStatus {
+    Pending,
+    Open,
+    /// Voting has ended on a commit-reveal poll, committed ballots can be revealed
+    Revealing,
+    Expired,
+    Closed,
+}
+
+impl fmt::Display for PollStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PollStatus::Pending => write!(f, "pending"),
+            PollStatus::Open => write!(f, "open"),
+            PollStatus::Revealing => write!(f, "revealing"),
+            PollStatus::Expired => write!(f, "expired"),
+            PollStatus::Closed => write!(f, "closed"),
+        }
+    }
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Ballot {
-    pub option: String
+    /// Ordered by preference for ranked choice and Borda polls
+    pub options: Vec<String>,
+    /// What the ballot added to `Poll.options`, taken back when the voter changes their ballot
+    pub points: Vec<(String, Decimal256)>,
+}
+
+impl Ballot {
+    /// Voting power of a ranked choice ballot, which only gives points to its first preference
+    pub fn power(&self) -> Decimal256 {
+        self.points.first().map_or(Decimal256::zero(), |(_, points)| *points)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -20,5 +231,65 @@ pub struct Config {
     pub admin : Addr,
 }
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const POLLS: Map<String, Poll> = Map::new("polls");
-pub const BALLOTS: Map<(Addr, String), Ballot> = Map::new("ballots");
\ No newline at end of file
+
+pub struct PollIndexes<'a> {
+    pub creator: MultiIndex<'a, Addr, Poll, String>,
+    // indexes the stored status only, which is either open or closed
+    pub status: MultiIndex<'a, String, Poll, String>,
+}
+
+impl<'a> IndexList<Poll> for PollIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Poll>> + '_> {
+        let v: Vec<&dyn Index<Poll>> = vec![&self.creator, &self.status];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn polls<'a>() -> IndexedMap<'a, String, Poll, PollIndexes<'a>> {
+    let indexes = PollIndexes {
+        creator: MultiIndex::new(|_pk, p| p.creator.clone(), "polls", "polls__creator"),
+        status: MultiIndex::new(|_pk, p| p.status.to_string(), "polls", "polls__status"),
+    };
+    IndexedMap::new("polls", indexes)
+}
+// pairwise preferences of ranked choice polls, [i][j] counts the votes preferring option i over j
+pub const PAIRWISE: Map<String, Vec<Vec<Decimal256>>> = Map::new("pairwise");
+// ballots of cw721 polls, keyed (poll_id, token_id) so that tokens keep them when changing hands
+pub const TOKEN_BALLOTS: Map<(String, String), Ballot> = Map::new("token_ballots");
+// addresses allowed to vote on polls with an allowlist, keyed (poll_id, voter)
+pub const VOTERS: Map<(String, Addr), ()> = Map::new("voters");
+// weights proven by voters of polls with a merkle root, keyed (voter, poll_id) like the ballots
+pub const CLAIMS: Map<(Addr, String), Uint128> = Map::new("claims");
+// hex encoded ballot commitments of commit-reveal polls, keyed (voter, poll_id) like the ballots
+pub const COMMITMENTS: Map<(Addr, String), String> = Map::new("commitments");
+// coins and cw20 tokens locked by voters of token polls, keyed (voter, poll_id) like the ballots
+pub const DEPOSITS: Map<(Addr, String), Uint128> = Map::new("deposits");
+// last id handed out to a poll created without an explicit poll_id
+pub const POLL_COUNT: Item<u64> = Item::new("poll_count");
+
+pub struct BallotIndexes<'a> {
+    // ballots are keyed by (voter, poll_id), this allows listing all ballots of a poll
+    pub poll: MultiIndex<'a, String, Ballot, (Addr, String)>,
+}
+
+impl<'a> IndexList<Ballot> for BallotIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Ballot>> + '_> {
+        let v: Vec<&dyn Index<Ballot>> = vec![&self.poll];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn ballots<'a>() -> IndexedMap<'a, (Addr, String), Ballot, BallotIndexes<'a>> {
+    let indexes = BallotIndexes {
+        poll: MultiIndex::new(
+            |pk, _| {
+                let (_, poll_id) = <(Addr, String)>::from_slice(pk)
+                    .expect("ballot keys are (voter, poll_id) pairs");
+                poll_id
+            },
+            "ballots",
+            "ballots__poll",
+        ),
+    };
+    IndexedMap::new("ballots", indexes)
+}
\ No newline at end of file
@@ -0,0 +1,192 @@
+use cosmwasm_std::{Decimal, Decimal256, Uint128};
+
+use crate::msg::RunoffRound;
+use crate::state::GovernanceOutcome;
+
+/// Runs instant-runoff elimination over ranked ballots, each counting with its voting power.
+/// Every round counts each ballot towards its highest ranked option still in the race, until one
+/// option holds a strict majority of the votes that are not exhausted yet. Otherwise all options
+/// tied for the fewest votes are eliminated together. Returns the rounds along with the winners,
+/// more than one meaning a tie.
+pub fn instant_runoff(
+    options: &[String],
+    rankings: &[(Vec<String>, Decimal256)],
+) -> (Vec<RunoffRound>, Vec<String>) {
+    let mut remaining: Vec<&String> = options.iter().collect();
+    let mut rounds = vec![];
+
+    // no sum below can overflow, it is at most the sum of the poll's first preference tallies
+    loop {
+        let mut tallies: Vec<(String, Decimal256)> = remaining
+            .iter()
+            .map(|option| (option.to_string(), Decimal256::zero()))
+            .collect();
+        for (ranking, power) in rankings {
+            if let Some(choice) = ranking.iter().find(|choice| remaining.contains(choice)) {
+                if let Some(tally) = tallies.iter_mut().find(|(option, _)| option == choice) {
+                    tally.1 += *power;
+                }
+            }
+        }
+
+        let active: Decimal256 = tallies.iter().map(|(_, count)| count).sum();
+        if active.is_zero() {
+            rounds.push(RunoffRound {
+                tallies,
+                eliminated: vec![],
+            });
+            return (rounds, vec![]);
+        }
+        if let Some((winner, _)) = tallies.iter().find(|(_, count)| *count > active - *count) {
+            let winners = vec![winner.clone()];
+            rounds.push(RunoffRound {
+                tallies,
+                eliminated: vec![],
+            });
+            return (rounds, winners);
+        }
+
+        let fewest = tallies
+            .iter()
+            .map(|(_, count)| *count)
+            .min()
+            .unwrap_or_else(Decimal256::zero);
+        let eliminated: Vec<String> = tallies
+            .iter()
+            .filter(|(_, count)| *count == fewest)
+            .map(|(option, _)| option.clone())
+            .collect();
+        // everyone left is tied, eliminating them all would leave nobody
+        if eliminated.len() == remaining.len() {
+            rounds.push(RunoffRound {
+                tallies,
+                eliminated: vec![],
+            });
+            return (rounds, eliminated);
+        }
+
+        remaining.retain(|option| !eliminated.contains(option));
+        rounds.push(RunoffRound {
+            tallies,
+            eliminated,
+        });
+    }
+}
+
+/// Adds (or takes back when `revoke` is set) the pairwise preferences expressed by a ranking
+/// with the given voting power to the matrix, where `matrix[i][j]` counts the votes preferring
+/// option `i` over option `j`. Ranked options are preferred over all unranked ones, unranked ones
+/// are left tied.
+pub fn record_preferences(
+    matrix: &mut [Vec<Decimal256>],
+    options: &[String],
+    ranking: &[String],
+    power: Decimal256,
+    revoke: bool,
+) {
+    let positions: Vec<usize> = ranking
+        .iter()
+        .filter_map(|choice| options.iter().position(|option| option == choice))
+        .collect();
+    for (rank, &i) in positions.iter().enumerate() {
+        for (j, count) in matrix[i].iter_mut().enumerate() {
+            if j == i || positions[..=rank].contains(&j) {
+                continue;
+            }
+            // at most the sum of the poll's first preference tallies, which can't overflow
+            if revoke {
+                *count -= power;
+            } else {
+                *count += power;
+            }
+        }
+    }
+}
+
+/// Orders the options by the Schulze method, based on the pairwise preference matrix. Returns
+/// groups of option indexes from most to least preferred, options in the same group are tied.
+pub fn schulze(matrix: &[Vec<Decimal256>]) -> Vec<Vec<usize>> {
+    let n = matrix.len();
+
+    // strength of the strongest path from i to j, starting from the direct wins
+    let mut paths = vec![vec![Decimal256::zero(); n]; n];
+    for i in 0..n {
+        for j in 0..n {
+            if i != j && matrix[i][j] > matrix[j][i] {
+                paths[i][j] = matrix[i][j];
+            }
+        }
+    }
+    for i in 0..n {
+        for j in 0..n {
+            if i == j {
+                continue;
+            }
+            for k in 0..n {
+                if i != k && j != k {
+                    paths[j][k] = paths[j][k].max(paths[j][i].min(paths[i][k]));
+                }
+            }
+        }
+    }
+
+    // repeatedly take the options no remaining option beats
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut ranking = vec![];
+    while !remaining.is_empty() {
+        let (unbeaten, beaten): (Vec<usize>, Vec<usize>) = remaining
+            .iter()
+            .partition(|&&i| remaining.iter().all(|&j| paths[j][i] <= paths[i][j]));
+        ranking.push(unbeaten);
+        remaining = beaten;
+    }
+    ranking
+}
+
+/// Credits spent by a quadratic voting ballot, casting n votes on an option costs n² credits
+pub fn quadratic_cost(votes: &[(String, Decimal256)]) -> Decimal256 {
+    votes
+        .iter()
+        .map(|(_, n)| n.saturating_mul(*n))
+        .fold(Decimal256::zero(), Decimal256::saturating_add)
+}
+
+/// Outcome of a governance poll given its yes, no, abstain and no_with_veto tallies
+pub fn governance_outcome(
+    tallies: &[(String, Decimal256)],
+    quorum: Uint128,
+    threshold: Decimal,
+    veto_threshold: Decimal,
+) -> GovernanceOutcome {
+    let quorum = Decimal256::from_ratio(quorum, 1u128);
+    let threshold = Decimal256::from(threshold);
+    let veto_threshold = Decimal256::from(veto_threshold);
+    let tally_of = |name: &str| {
+        tallies
+            .iter()
+            .find(|(option, _)| option == name)
+            .map_or(Decimal256::zero(), |(_, tally)| *tally)
+    };
+    let (yes, no, abstain, veto) = (
+        tally_of("yes"),
+        tally_of("no"),
+        tally_of("abstain"),
+        tally_of("no_with_veto"),
+    );
+    // can't overflow, ballots making the tallies of a poll add up to more than fits are rejected
+    let total = yes + no + abstain + veto;
+
+    if total.is_zero() || total < quorum {
+        return GovernanceOutcome::QuorumNotReached;
+    }
+    if veto / total > veto_threshold {
+        return GovernanceOutcome::RejectedWithVeto;
+    }
+    // abstentions count towards the quorum only
+    let voting = total - abstain;
+    if !voting.is_zero() && yes / voting > threshold {
+        GovernanceOutcome::Passed
+    } else {
+        GovernanceOutcome::Rejected
+    }
+}